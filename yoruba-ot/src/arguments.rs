@@ -0,0 +1,158 @@
+use crate::corpus::Datum;
+use crate::erc::{set_entails, Erc, Preference};
+use crate::gen::Gen;
+use crate::learn::{constraint_names, tableaux, ConstraintSet, LearningError};
+use crate::rcd::{all_ercs, rank};
+
+// what a set of data tells us about the ranking, in the terms ranking arguments usually get made
+// in: the ERCs it takes (see erc.rs), without any that follow from the rest, and the pairwise
+// rankings every ranking consistent with the data has to have, as a Hasse diagram
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankingArguments {
+    pub ercs: Vec<Erc>,
+    // each of these is a constraint that has to outrank another, as indices into the constraint
+    // set, leaving out whatever follows from the rest by transitivity
+    pub hasse: Vec<(usize, usize)>,
+}
+
+// the ERCs, without any that are all es, any repeats, or any that follow from the others; ERCs
+// that follow from a single other one go first, so that it's the stronger ones that are kept
+pub fn minimal(ercs: &[Erc]) -> Vec<Erc> {
+    let mut left: Vec<Erc> = ercs.iter().fold(Vec::new(), |mut left: Vec<Erc>, erc| {
+        if !erc.is_trivial()
+            && !left
+                .iter()
+                .any(|other| other.preferences == erc.preferences)
+        {
+            left.push(erc.to_owned());
+        }
+
+        left
+    });
+
+    left = left
+        .iter()
+        .enumerate()
+        .filter(|(index, erc)| {
+            !left.iter().enumerate().any(|(other_index, other)| {
+                other_index != *index
+                    && other.entails(erc)
+                    // of two ERCs that entail each other, only the first is kept
+                    && (!erc.entails(other) || other_index < *index)
+            })
+        })
+        .map(|(_, erc)| erc.to_owned())
+        .collect();
+
+    // from the end, so that it's the ERCs that come first that get kept
+    (0..left.len()).rev().for_each(|index| {
+        let others: Vec<Erc> = left
+            .iter()
+            .enumerate()
+            .filter(|(other_index, _)| *other_index != index)
+            .map(|(_, other)| other.to_owned())
+            .collect();
+
+        if set_entails(&others, &left[index]) {
+            left.remove(index);
+        }
+    });
+
+    left
+}
+
+// every pair of constraints where the first has to outrank the second
+pub fn necessary_rankings(ercs: &[Erc]) -> Vec<(usize, usize)> {
+    let count = ercs.first().map_or(0, |erc| erc.preferences.len());
+
+    (0..count)
+        .flat_map(|higher| (0..count).map(move |lower| (higher, lower)))
+        .filter(|(higher, lower)| higher != lower)
+        .filter(|(higher, lower)| {
+            set_entails(
+                ercs,
+                &Erc {
+                    winner: String::new(),
+                    loser: String::new(),
+                    preferences: (0..count)
+                        .map(|constraint| {
+                            if constraint == *higher {
+                                Preference::W
+                            } else if constraint == *lower {
+                                Preference::L
+                            } else {
+                                Preference::E
+                            }
+                        })
+                        .collect(),
+                },
+            )
+        })
+        .collect()
+}
+
+// the rankings that don't follow from any two others; necessary rankings are already closed under
+// transitivity, so we only have to look one step away
+pub fn hasse(rankings: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    rankings
+        .iter()
+        .copied()
+        .filter(|(higher, lower)| {
+            !rankings
+                .iter()
+                .any(|(first, middle)| first == higher && rankings.contains(&(*middle, *lower)))
+        })
+        .collect()
+}
+
+pub fn ranking_arguments(
+    data: &[Datum],
+    constraints: ConstraintSet,
+    gen: &Gen,
+) -> Result<RankingArguments, LearningError> {
+    let ercs = all_ercs(&tableaux(data, constraints, gen)?);
+
+    // inconsistent data would entail every ranking there is, so we'd rather hear about it
+    rank(&ercs, &constraint_names(constraints))?;
+
+    Ok(RankingArguments {
+        hasse: hasse(&necessary_rankings(&ercs)),
+        ercs: minimal(&ercs),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::erc::erc;
+
+    #[test]
+    fn test_minimal_1() {
+        let ercs = [
+            // C0 >> C1
+            erc("WLe"),
+            // C1 >> C2
+            erc("eWL"),
+            // C0 >> C2, which follows from the two of them
+            erc("WeL"),
+            // C0 or C2 >> C1, which follows from the first
+            erc("WLW"),
+            erc("WLe"),
+            erc("eee"),
+        ];
+
+        assert_eq!(minimal(&ercs), ercs[..2].to_vec());
+    }
+
+    #[test]
+    fn test_necessary_rankings_1() {
+        // C0 >> C1 >> C2, with C3 and C4 each outranking C2 some way or another
+        let ercs = [erc("WLeee"), erc("eWLee"), erc("eeLWW")];
+
+        let rankings = necessary_rankings(&ercs);
+
+        assert_eq!(rankings, vec![(0, 1), (0, 2), (1, 2)]);
+        assert_eq!(hasse(&rankings), vec![(0, 1), (1, 2)]);
+    }
+}
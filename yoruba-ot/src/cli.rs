@@ -0,0 +1,327 @@
+use crate::features::NaturalClass;
+use crate::gen::{Epenthesis, Gen, Metathesis, Substitution};
+use crate::gla::GlaSettings;
+use crate::learn::Learner;
+use crate::sgd::SgdSettings;
+use crate::typology::Method;
+use crate::EvalMode;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    // everything GEN does except substitution, which is left in substitution until the inventory
+    // has been loaded
+    pub gen: Gen,
+    pub substitution: Option<SubstitutionOptions>,
+    pub inventory: Option<String>,
+    pub mode: EvalMode,
+    // weigh the constraints instead of ranking them (see harmonic.rs), with --harmonic; the mode
+    // doesn't matter then
+    pub harmonic: bool,
+    // print a MaxEnt probability for every output with the same weights (see maxent.rs), with
+    // --maxent
+    pub maxent: bool,
+    // how much noise to evaluate with in Stochastic OT (see stochastic.rs), with --stochastic, and
+    // over how many evaluations, with --trials
+    pub stochastic: Option<f64>,
+    pub trials: usize,
+    // a corpus to learn the grammar from, with --learn, and what to learn it with, with --learner
+    // rcd, bcd (see rcd.rs), gla (see gla.rs) or maxent (see sgd.rs); --plasticity goes to the
+    // GLA, which learns with the --stochastic noise if there is one, --learning-rate and --prior
+    // go to MaxEnt, and --epochs goes to both
+    pub learn: Option<String>,
+    // print the ranking arguments the --learn corpus makes before learning from it (see
+    // arguments.rs), with --arguments
+    pub arguments: bool,
+    // a file to write the ranking to as a Graphviz graph (see dot.rs), with --dot: the crucial
+    // dominations with --arguments, otherwise whatever was learned, otherwise the hand-written
+    // ranking
+    pub dot: Option<String>,
+    // a list of underlying forms (or a corpus) to work out the factorial typology over (see
+    // typology.rs), with --typology, going through every ranking with --brute-force
+    pub typology: Option<String>,
+    pub typology_method: Method,
+    // a list of underlying forms (or a corpus) to print a LaTeX tableau for each of (see
+    // latex.rs), with --latex, spelling the forms in tipa with --tipa
+    pub latex: Option<String>,
+    pub tipa: bool,
+    pub learner: Learner,
+    pub gla: GlaSettings,
+    pub sgd: SgdSettings,
+}
+
+// what --substitution (any vowel), --substitute-class, --substitute-consonants and
+// --max-substitution ask for, any of which turns substitution on; natural classes are only turned
+// into segments by substitution(), since the segments come from the inventory and --inventory may
+// come later on the command line
+#[derive(Debug, Clone)]
+pub struct SubstitutionOptions {
+    pub vowels: bool,
+    // every --substitute-class given, each adding its segments to the rest
+    pub classes: Vec<NaturalClass>,
+    pub consonants: Vec<String>,
+    pub max: usize,
+}
+
+impl Default for SubstitutionOptions {
+    fn default() -> Self {
+        SubstitutionOptions {
+            vowels: false,
+            classes: Vec::new(),
+            consonants: Vec::new(),
+            max: 1,
+        }
+    }
+}
+
+impl SubstitutionOptions {
+    pub fn substitution(self) -> Substitution {
+        let mut substitution = Substitution {
+            max: self.max,
+            ..Substitution::default()
+        };
+
+        if self.vowels {
+            substitution.add(Substitution::vowels(self.max).segments);
+        }
+
+        self.classes.iter().for_each(|class| {
+            substitution.add(Substitution::natural_class(class, self.max).segments)
+        });
+        substitution.add(self.consonants);
+
+        substitution
+    }
+}
+
+impl Options {
+    // reads the command line, e.g.
+    //
+    //     yoruba-ot --inventory inventories/yoruba.txt --learn data/elision.txt --arguments \
+    //         --dot ranking.dot --learner bcd \
+    //         --mode strict \
+    //         --epenthesis i,j --max-epenthesis 2 --substitution --substitute-consonants l,n \
+    //         --substitute-class +syllabic,+high \
+    //         --metathesis --max-metathesis 1 --floating-tones
+    pub fn from_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut inventory: Option<String> = None;
+        let mut mode = EvalMode::default();
+        let mut harmonic = false;
+        let mut maxent = false;
+        let mut stochastic: Option<f64> = None;
+        let mut trials = 1000;
+        let mut learn: Option<String> = None;
+        let mut arguments = false;
+        let mut dot: Option<String> = None;
+        let mut typology: Option<String> = None;
+        let mut typology_method = Method::default();
+        let mut latex: Option<String> = None;
+        let mut tipa = false;
+        let mut learner = Learner::default();
+        let mut gla = GlaSettings::default();
+        let mut sgd = SgdSettings::default();
+        let mut epochs: Option<usize> = None;
+        let mut epenthetic_segments: Option<Vec<String>> = None;
+        let mut max_epenthesis = 1;
+        let mut substitution: Option<SubstitutionOptions> = None;
+        let mut metathesis = false;
+        let mut max_metathesis = 1;
+        let mut floating_tones = false;
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("Missing value for {}", arg))
+            };
+
+            match arg.as_str() {
+                "--inventory" => inventory = Some(value()?),
+                "--mode" => {
+                    mode = match value()?.as_str() {
+                        "cumulative" => EvalMode::Cumulative,
+                        "strict" => EvalMode::Strict,
+                        "refinements" => EvalMode::Refinements,
+                        other => return Err(format!("Unknown evaluation mode {}", other)),
+                    }
+                }
+                "--harmonic" => harmonic = true,
+                "--maxent" => maxent = true,
+                "--stochastic" => stochastic = Some(number(&arg, value()?)?),
+                "--trials" => trials = number(&arg, value()?)?,
+                "--learn" => learn = Some(value()?),
+                "--arguments" => arguments = true,
+                "--dot" => dot = Some(value()?),
+                "--typology" => typology = Some(value()?),
+                "--brute-force" => typology_method = Method::BruteForce,
+                "--latex" => latex = Some(value()?),
+                "--tipa" => tipa = true,
+                "--learner" => {
+                    learner = match value()?.as_str() {
+                        "rcd" => Learner::Rcd,
+                        "bcd" => Learner::Bcd,
+                        "gla" => Learner::Gla,
+                        "maxent" => Learner::MaxEnt,
+                        other => return Err(format!("Unknown learner {}", other)),
+                    }
+                }
+                "--epochs" => epochs = Some(number(&arg, value()?)?),
+                "--plasticity" => gla.plasticity = number(&arg, value()?)?,
+                "--learning-rate" => sgd.learning_rate = number(&arg, value()?)?,
+                "--prior" => sgd.prior = Some(number(&arg, value()?)?),
+                "--epenthesis" => {
+                    epenthetic_segments =
+                        Some(value()?.split(',').map(|seg| seg.to_owned()).collect())
+                }
+                "--max-epenthesis" => max_epenthesis = number(&arg, value()?)?,
+                "--substitution" => {
+                    substitution
+                        .get_or_insert_with(SubstitutionOptions::default)
+                        .vowels = true
+                }
+                "--substitute-class" => substitution
+                    .get_or_insert_with(SubstitutionOptions::default)
+                    .classes
+                    .push(natural_class(value()?)?),
+                "--substitute-consonants" => substitution
+                    .get_or_insert_with(SubstitutionOptions::default)
+                    .consonants
+                    .extend(value()?.split(',').map(|seg| seg.to_owned())),
+                "--max-substitution" => {
+                    substitution
+                        .get_or_insert_with(SubstitutionOptions::default)
+                        .max = number(&arg, value()?)?
+                }
+                "--metathesis" => metathesis = true,
+                "--max-metathesis" => max_metathesis = number(&arg, value()?)?,
+                "--floating-tones" => floating_tones = true,
+                _ => return Err(format!("Unknown argument {}", arg)),
+            }
+        }
+
+        Ok(Options {
+            gen: Gen {
+                epenthesis: epenthetic_segments.map(|segments| Epenthesis {
+                    segments,
+                    max: max_epenthesis,
+                }),
+                substitution: None,
+                metathesis: metathesis.then_some(Metathesis {
+                    max: max_metathesis,
+                }),
+                floating_tones,
+            },
+            substitution,
+            inventory,
+            mode,
+            harmonic,
+            maxent,
+            stochastic,
+            trials,
+            learn,
+            arguments,
+            dot,
+            typology,
+            typology_method,
+            latex,
+            tipa,
+            learner,
+            gla: GlaSettings {
+                noise: stochastic.unwrap_or(gla.noise),
+                epochs: epochs.unwrap_or(gla.epochs),
+                ..gla
+            },
+            sgd: SgdSettings {
+                epochs: epochs.unwrap_or(sgd.epochs),
+                ..sgd
+            },
+        })
+    }
+}
+
+// the value of a flag that takes a number
+fn number<T: FromStr>(arg: &str, value: String) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} takes a number, not {}", arg, value))
+}
+
+fn natural_class(value: String) -> Result<NaturalClass, String> {
+    NaturalClass::parse(&value).ok_or_else(|| format!("{} is not a natural class", value))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cli::Options;
+    use crate::gen::Substitution;
+
+    fn substitution(args: &[&str]) -> Option<Substitution> {
+        Options::from_args(args.iter().map(|arg| arg.to_string()))
+            .unwrap()
+            .substitution
+            .map(|substitution| substitution.substitution())
+    }
+
+    #[test]
+    fn test_substitution_1() {
+        // consonants on their own are enough to substitute with
+        assert_eq!(
+            substitution(&["--substitute-consonants", "l,n"]),
+            Some(Substitution {
+                segments: vec!["l".to_owned(), "n".to_owned()],
+                max: 1,
+            })
+        );
+
+        // and they add to the vowels, whichever order they're given in
+        let with_vowels = substitution(&[
+            "--substitute-consonants",
+            "l",
+            "--max-substitution",
+            "2",
+            "--substitution",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            with_vowels.segments.len(),
+            Substitution::vowels(1).segments.len() + 1
+        );
+        assert_eq!(with_vowels.max, 2);
+    }
+
+    #[test]
+    fn test_substitution_2() {
+        // a class adds to the vowels rather than replacing them, and the high vowels it adds are
+        // already there
+        assert_eq!(
+            substitution(&["--substitution", "--substitute-class", "+syllabic,+high"]),
+            Some(Substitution::vowels(1))
+        );
+    }
+
+    #[test]
+    fn test_from_args_1() {
+        let from_args = |args: &[&str]| Options::from_args(args.iter().map(|arg| arg.to_string()));
+
+        assert_eq!(
+            from_args(&["--max-epenthesis", "two"]).unwrap_err(),
+            "--max-epenthesis takes a number, not two"
+        );
+        assert_eq!(
+            from_args(&["--substitute-class", "high"]).unwrap_err(),
+            "high is not a natural class"
+        );
+        assert_eq!(
+            from_args(&["--epenthesis"]).unwrap_err(),
+            "Missing value for --epenthesis"
+        );
+        assert_eq!(
+            from_args(&["--mode", "fast"]).unwrap_err(),
+            "Unknown evaluation mode fast"
+        );
+        assert_eq!(
+            from_args(&["--elision"]).unwrap_err(),
+            "Unknown argument --elision"
+        );
+    }
+}
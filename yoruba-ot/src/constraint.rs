@@ -1,10 +1,29 @@
 use crate::{SyllabifiedCandidate, SyllableIndex, UnderlyingIndex};
-use similar::{DiffOp, TextDiff};
 
 // need to make this a subtrait of debug since we need to tell rust that everything that implements
 // Constraint must implement Debug since we're using trait objects
 pub trait Constraint: std::fmt::Debug {
     fn evaluate(&self, surface: SyllabifiedCandidate) -> usize;
+
+    fn kind(&self) -> ConstraintKind;
+
+    // what to call the constraint when showing it to people, e.g. in the hierarchies the learners
+    // come up with; the name of the type unless it's got a more usual one
+    fn name(&self) -> String {
+        std::any::type_name::<Self>()
+            .rsplit("::")
+            .next()
+            .unwrap()
+            .to_owned()
+    }
+}
+
+// markedness constraints only look at the surface form; faithfulness constraints compare it to the
+// underlying one
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ConstraintKind {
+    Markedness,
+    Faithfulness,
 }
 
 #[derive(Debug)]
@@ -17,6 +36,14 @@ impl Constraint for RankedConstraint {
     fn evaluate(&self, surface: SyllabifiedCandidate) -> usize {
         self.constraint.evaluate(surface)
     }
+
+    fn kind(&self) -> ConstraintKind {
+        self.constraint.kind()
+    }
+
+    fn name(&self) -> String {
+        self.constraint.name()
+    }
 }
 
 impl Constraint for Vec<&RankedConstraint> {
@@ -24,6 +51,18 @@ impl Constraint for Vec<&RankedConstraint> {
         self.iter()
             .fold(0, |prev, next| prev + next.evaluate(surface.clone()))
     }
+
+    // a stratum only counts as faithfulness if everything in it is
+    fn kind(&self) -> ConstraintKind {
+        if self
+            .iter()
+            .all(|constraint| constraint.kind() == ConstraintKind::Faithfulness)
+        {
+            ConstraintKind::Faithfulness
+        } else {
+            ConstraintKind::Markedness
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -31,16 +70,21 @@ pub struct Ident(pub SyllabifiedCandidate);
 
 impl Constraint for Ident {
     fn evaluate(&self, surface: SyllabifiedCandidate) -> usize {
-        let self_str: String = self.clone().0.into();
-        let surface_str: String = surface.into();
-
-        let diff = TextDiff::from_graphemes::<String>(&self_str, &surface_str);
-
-        diff.ops()
+        surface
+            .form
             .iter()
-            .filter(|op| matches!(op, DiffOp::Replace { .. }))
+            .filter(|seg| {
+                self.0.correspondent(seg).is_some_and(|underlying| {
+                    underlying.char != seg.char
+                        || self.0.tones_of(underlying) != surface.tones_of(seg)
+                })
+            })
             .count()
     }
+
+    fn kind(&self) -> ConstraintKind {
+        ConstraintKind::Faithfulness
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -48,58 +92,82 @@ pub struct Dep(pub SyllabifiedCandidate);
 
 impl Constraint for Dep {
     fn evaluate(&self, surface: SyllabifiedCandidate) -> usize {
-        let self_str: String = self.clone().0.into();
-        let surface_str: String = surface.into();
-
-        let diff = TextDiff::from_graphemes::<String>(&self_str, &surface_str);
-
-        diff.ops()
+        surface
+            .form
             .iter()
-            .filter(|op| matches!(op, DiffOp::Insert { .. }))
+            .filter(|seg| self.0.correspondent(seg).is_none())
             .count()
     }
+
+    fn kind(&self) -> ConstraintKind {
+        ConstraintKind::Faithfulness
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Onset;
 
+// Onset and Max count each violation three times over, which is what lets them outweigh SonSeqPr
+// in the stratum they share; UnweightedOnset, UnweightedMax and Anchor are the same constraints
+// without that baked in, for weighted grammars (see harmonic.rs) to put their own weights on; the
+// unweighted ones go by the usual names, so these are Onset*3 and Max*3
 impl Constraint for Onset {
     fn evaluate(&self, surface: SyllabifiedCandidate) -> usize {
-        let syllabi = surface
-            .form
-            .iter()
-            .filter(|seg| seg.syllable_index == SyllableIndex::Nucleus)
-            .count();
+        onsetless(&surface) * 3
+    }
 
-        let onsets = surface
-            .form
-            .iter()
-            .filter(|seg| seg.syllable_index == SyllableIndex::Onset)
-            .count();
+    fn kind(&self) -> ConstraintKind {
+        ConstraintKind::Markedness
+    }
 
-        (syllabi - onsets) * 3
+    fn name(&self) -> String {
+        "Onset*3".to_owned()
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnweightedOnset;
+
+impl Constraint for UnweightedOnset {
+    fn evaluate(&self, surface: SyllabifiedCandidate) -> usize {
+        onsetless(&surface)
+    }
+
+    fn kind(&self) -> ConstraintKind {
+        ConstraintKind::Markedness
+    }
+
+    fn name(&self) -> String {
+        "Onset".to_owned()
+    }
+}
+
+fn onsetless(surface: &SyllabifiedCandidate) -> usize {
+    let syllabi = surface
+        .form
+        .iter()
+        .filter(|seg| seg.syllable_index == SyllableIndex::Nucleus)
+        .count();
+
+    let onsets = surface
+        .form
+        .iter()
+        .filter(|seg| seg.syllable_index == SyllableIndex::Onset)
+        .count();
+
+    syllabi - onsets
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct SonSeqPr;
 
 impl Constraint for SonSeqPr {
     fn evaluate(&self, surface: SyllabifiedCandidate) -> usize {
-        surface
-            .form
-            .iter()
-            // a hack to ignore accent marks
-            //
-            // .next().unwrap() should never panic here bc that's only possible if the initial
-            // candidate input string is empty, and if that's true, then the iterator will be empty
-            .map(|seg| match seg.char.chars().next().unwrap() {
-                'e' | 'ɛ' | 'o' | 'ɔ' => 1,
-                'u' => 2,
-                'i' => 3,
-                _ => 0,
-            })
-            .sum()
+        surface.form.iter().map(|seg| seg.sonority()).sum()
+    }
+
+    fn kind(&self) -> ConstraintKind {
+        ConstraintKind::Markedness
     }
 }
 
@@ -114,6 +182,10 @@ impl Constraint for Syllabify {
             .filter(|seg| seg.syllable_index == SyllableIndex::None)
             .count()
     }
+
+    fn kind(&self) -> ConstraintKind {
+        ConstraintKind::Markedness
+    }
 }
 
 #[derive(Debug)]
@@ -121,17 +193,77 @@ pub struct Max(pub SyllabifiedCandidate);
 
 impl Constraint for Max {
     fn evaluate(&self, surface: SyllabifiedCandidate) -> usize {
-        let mut violations = (self.0.form.len() - surface.form.len()) * 3;
+        deleted(&self.0, &surface) * 3 + moved_edges(&self.0, &surface)
+    }
 
-        if !surface.form.is_empty()
-            && (surface.form[0].char != self.0.form[0].char
-                || surface.form[surface.form.len() - 1].char
-                    != self.0.form[self.0.form.len() - 1].char)
-        {
-            violations += 1;
-        }
+    fn kind(&self) -> ConstraintKind {
+        ConstraintKind::Faithfulness
+    }
+
+    fn name(&self) -> String {
+        "Max*3".to_owned()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnweightedMax(pub SyllabifiedCandidate);
+
+impl Constraint for UnweightedMax {
+    fn evaluate(&self, surface: SyllabifiedCandidate) -> usize {
+        deleted(&self.0, &surface)
+    }
 
-        violations
+    fn kind(&self) -> ConstraintKind {
+        ConstraintKind::Faithfulness
+    }
+
+    fn name(&self) -> String {
+        "Max".to_owned()
+    }
+}
+
+// the edges of the word have to stay put
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anchor(pub SyllabifiedCandidate);
+
+impl Constraint for Anchor {
+    fn evaluate(&self, surface: SyllabifiedCandidate) -> usize {
+        moved_edges(&self.0, &surface)
+    }
+
+    fn kind(&self) -> ConstraintKind {
+        ConstraintKind::Faithfulness
+    }
+}
+
+fn deleted(underlying: &SyllabifiedCandidate, surface: &SyllabifiedCandidate) -> usize {
+    underlying
+        .form
+        .iter()
+        .filter(|underlying_seg| {
+            !surface
+                .form
+                .iter()
+                .any(|seg| underlying.correspondent(seg) == Some(underlying_seg))
+        })
+        .count()
+}
+
+fn moved_edges(underlying: &SyllabifiedCandidate, surface: &SyllabifiedCandidate) -> usize {
+    // deleting the whole word takes its edges along with it
+    let first = surface
+        .form
+        .first()
+        .and_then(|seg| underlying.correspondent(seg));
+    let last = surface
+        .form
+        .last()
+        .and_then(|seg| underlying.correspondent(seg));
+
+    if first != underlying.form.first() || last != underlying.form.last() {
+        1
+    } else {
+        0
     }
 }
 
@@ -155,6 +287,10 @@ impl Constraint for MaxInitialV {
 
         underlying_init - surface_init
     }
+
+    fn kind(&self) -> ConstraintKind {
+        ConstraintKind::Faithfulness
+    }
 }
 
 #[derive(Debug)]
@@ -177,4 +313,108 @@ impl Constraint for MaxFinalV {
 
         underlying_final - surface_final
     }
+
+    fn kind(&self) -> ConstraintKind {
+        ConstraintKind::Faithfulness
+    }
+}
+
+#[derive(Debug)]
+pub struct Linearity(pub SyllabifiedCandidate);
+
+impl Constraint for Linearity {
+    fn evaluate(&self, surface: SyllabifiedCandidate) -> usize {
+        // where each surface segment's correspondent sits in the underlying form
+        let positions: Vec<usize> = surface
+            .form
+            .iter()
+            .filter_map(|seg| {
+                self.0
+                    .correspondent(seg)
+                    .and_then(|underlying| self.0.form.iter().position(|other| other == underlying))
+            })
+            .collect();
+
+        // one violation for every pair of segments whose order was reversed
+        positions
+            .iter()
+            .enumerate()
+            .map(|(idx, position)| {
+                positions[idx + 1..]
+                    .iter()
+                    .filter(|later| *later < position)
+                    .count()
+            })
+            .sum()
+    }
+
+    fn kind(&self) -> ConstraintKind {
+        ConstraintKind::Faithfulness
+    }
+}
+
+#[derive(Debug)]
+pub struct MaxTone(pub SyllabifiedCandidate);
+
+impl Constraint for MaxTone {
+    fn evaluate(&self, surface: SyllabifiedCandidate) -> usize {
+        self.0
+            .tones
+            .iter()
+            .filter(|underlying| {
+                !surface
+                    .tones
+                    .iter()
+                    .any(|tone| tone.correspondent == underlying.correspondent)
+            })
+            .count()
+    }
+
+    fn kind(&self) -> ConstraintKind {
+        ConstraintKind::Faithfulness
+    }
+}
+
+// *Float
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoFloat;
+
+impl Constraint for NoFloat {
+    fn evaluate(&self, surface: SyllabifiedCandidate) -> usize {
+        surface
+            .tones
+            .iter()
+            .filter(|tone| tone.anchor.is_none())
+            .count()
+    }
+
+    fn kind(&self) -> ConstraintKind {
+        ConstraintKind::Markedness
+    }
+
+    fn name(&self) -> String {
+        "*Float".to_owned()
+    }
+}
+
+// the OCP over the tonal tier: no two adjacent tones may be the same, floating or not
+#[derive(Debug, Clone, PartialEq)]
+pub struct OcpTone;
+
+impl Constraint for OcpTone {
+    fn evaluate(&self, surface: SyllabifiedCandidate) -> usize {
+        surface
+            .tones
+            .windows(2)
+            .filter(|pair| pair[0].level == pair[1].level)
+            .count()
+    }
+
+    fn kind(&self) -> ConstraintKind {
+        ConstraintKind::Markedness
+    }
+
+    fn name(&self) -> String {
+        "OCP-Tone".to_owned()
+    }
 }
@@ -0,0 +1,149 @@
+use crate::SyllabifiedCandidate;
+
+// an observed surface form, along with the underlying form it comes from and how many times it was
+// observed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Datum {
+    pub underlying: SyllabifiedCandidate,
+    pub surface: String,
+    pub frequency: usize,
+}
+
+// reads a corpus: one datum per line, the underlying form, the surface form, and optionally how
+// many times it was observed (once if it isn't given), with anything after a # ignored (see
+// data/elision.txt); variable outputs go on lines of their own with the same underlying form
+pub fn parse_corpus(str: &str) -> Result<Vec<Datum>, String> {
+    str.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.split('#').next().unwrap().trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(line_number, line)| {
+            parse_datum(line).map_err(|err| format!("line {}: {}", line_number, err))
+        })
+        .collect()
+}
+
+pub fn read_corpus(path: &str) -> Result<Vec<Datum>, String> {
+    parse_corpus(&std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?)
+        .map_err(|err| format!("{}: {}", path, err))
+}
+
+// reads a list of underlying forms, one per line; this goes for corpora too, taking the first form
+// on each line
+pub fn parse_underlying(str: &str) -> Result<Vec<SyllabifiedCandidate>, String> {
+    str.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.split('#').next().unwrap().trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(line_number, line)| {
+            line.split_whitespace()
+                .next()
+                .unwrap()
+                .parse()
+                .map_err(|err| format!("line {}: {}", line_number, err))
+        })
+        .collect()
+}
+
+pub fn read_underlying(path: &str) -> Result<Vec<SyllabifiedCandidate>, String> {
+    parse_underlying(&std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?)
+        .map_err(|err| format!("{}: {}", path, err))
+}
+
+fn parse_datum(line: &str) -> Result<Datum, String> {
+    let mut fields = line.split_whitespace();
+
+    let underlying: SyllabifiedCandidate = fields
+        .next()
+        .ok_or("missing underlying form")?
+        .parse()
+        .map_err(|err| format!("{}", err))?;
+
+    // going through the parser so that the surface form is spelled the same way as the forms GEN
+    // comes up with (precomposed tones and so on)
+    let surface: SyllabifiedCandidate = fields
+        .next()
+        .ok_or("missing surface form")?
+        .parse()
+        .map_err(|err| format!("{}", err))?;
+
+    let frequency = match fields.next() {
+        Some(field) => field.parse().map_err(|_| format!("unexpected {}", field))?,
+        None => 1,
+    };
+
+    if let Some(field) = fields.next() {
+        return Err(format!("unexpected {}", field));
+    }
+
+    Ok(Datum {
+        underlying,
+        surface: surface.into(),
+        frequency,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_corpus_1() {
+        let corpus = parse_corpus(
+            "# a comment
+            owo-ki-owo owokowo
+
+            ra-ɔ\u{300}gɛdɛ rɔ\u{300}gɛdɛ 3 # and another",
+        )
+        .unwrap();
+
+        assert_eq!(corpus.len(), 2);
+        assert_eq!(corpus[0].surface, "owokowo");
+        assert_eq!(corpus[0].underlying.form.len(), 8);
+        assert_eq!(corpus[0].frequency, 1);
+        assert_eq!(corpus[1].surface, "rɔ\u{300}gɛdɛ");
+        assert_eq!(corpus[1].frequency, 3);
+    }
+
+    #[test]
+    fn test_parse_corpus_2() {
+        assert_eq!(
+            parse_corpus("owo-ki-owo owokowo\nowo-xi-owo owokowo"),
+            Err("line 2: unknown segment x at position 4".to_owned())
+        );
+        assert_eq!(
+            parse_corpus("owo-ki-owo"),
+            Err("line 1: missing surface form".to_owned())
+        );
+        assert_eq!(
+            parse_corpus("owo-ki-owo owokowo owokowo"),
+            Err("line 1: unexpected owokowo".to_owned())
+        );
+        assert_eq!(
+            parse_corpus("owo-ki-owo owokowo 2 owokowo"),
+            Err("line 1: unexpected owokowo".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_parse_underlying_1() {
+        let underlying = parse_underlying("owo-ki-owo\n\n# a comment\nra-ɔgɛdɛ ragɛdɛ 2").unwrap();
+
+        assert_eq!(underlying.len(), 2);
+        assert_eq!(String::from(underlying[1].to_owned()), "raɔgɛdɛ");
+        assert_eq!(
+            parse_underlying("owo-ki-owo\nowo-xi-owo"),
+            Err("line 2: unknown segment x at position 4".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_parse_corpus_3() {
+        assert_eq!(
+            parse_corpus(include_str!("../data/elision.txt"))
+                .unwrap()
+                .len(),
+            17
+        );
+    }
+}
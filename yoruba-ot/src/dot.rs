@@ -0,0 +1,47 @@
+// rankings as Graphviz graphs, for drawing Hasse diagrams with dot: a node for every constraint,
+// and an edge from each constraint to every one it crucially dominates, e.g.
+//
+//     digraph ranking {
+//         "Onset";
+//         "Max";
+//         "Onset" -> "Max";
+//     }
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// the edges are indices into names, higher ranked constraint first
+pub fn dot(names: &[String], edges: &[(usize, usize)]) -> String {
+    let nodes = names.iter().map(|name| format!("    {};\n", quote(name)));
+
+    let edges = edges.iter().map(|(higher, lower)| {
+        format!(
+            "    {} -> {};\n",
+            quote(&names[*higher]),
+            quote(&names[*lower])
+        )
+    });
+
+    format!(
+        "digraph ranking {{\n{}}}\n",
+        nodes.chain(edges).collect::<String>()
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_dot_1() {
+        let names = vec!["Onset".to_owned(), "Max".to_owned(), "*Float".to_owned()];
+
+        assert_eq!(
+            dot(&names, &[(0, 1), (0, 2)]),
+            "digraph ranking {\n    \"Onset\";\n    \"Max\";\n    \"*Float\";\n    \
+             \"Onset\" -> \"Max\";\n    \"Onset\" -> \"*Float\";\n}\n"
+        );
+        assert_eq!(quote("a\"b"), "\"a\\\"b\"");
+    }
+}
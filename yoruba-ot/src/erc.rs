@@ -0,0 +1,441 @@
+use crate::learn::Tableau;
+use unicode_segmentation::UnicodeSegmentation;
+
+// Elementary Ranking Conditions (Prince): what a winner/loser pair says about the ranking. each
+// constraint either prefers the winner (W), prefers the loser (L), or can't tell them apart (e);
+// for the winner to win, some constraint preferring it has to outrank every constraint preferring
+// the loser. a comparative tableau is just the ERCs for every loser of one winner
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Preference {
+    W,
+    L,
+    E,
+}
+
+impl std::fmt::Display for Preference {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Preference::W => write!(f, "W"),
+            Preference::L => write!(f, "L"),
+            Preference::E => write!(f, "e"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Erc {
+    pub winner: String,
+    pub loser: String,
+    // one per constraint
+    pub preferences: Vec<Preference>,
+}
+
+impl Erc {
+    pub fn new(
+        winner: &str,
+        loser: &str,
+        winner_violations: &[usize],
+        loser_violations: &[usize],
+    ) -> Erc {
+        Erc {
+            winner: winner.to_owned(),
+            loser: loser.to_owned(),
+            preferences: winner_violations
+                .iter()
+                .zip(loser_violations)
+                .map(|(winner, loser)| match winner.cmp(loser) {
+                    std::cmp::Ordering::Less => Preference::W,
+                    std::cmp::Ordering::Greater => Preference::L,
+                    std::cmp::Ordering::Equal => Preference::E,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn prefers_winner(&self, constraint: usize) -> bool {
+        self.preferences[constraint] == Preference::W
+    }
+
+    pub fn prefers_loser(&self, constraint: usize) -> bool {
+        self.preferences[constraint] == Preference::L
+    }
+
+    // no constraint can tell the winner from the loser, so no ranking has anything to say about
+    // them either
+    pub fn is_trivial(&self) -> bool {
+        self.preferences
+            .iter()
+            .all(|preference| *preference == Preference::E)
+    }
+
+    pub fn entails(&self, other: &Erc) -> bool {
+        self.preferences
+            .iter()
+            .zip(&other.preferences)
+            .all(|(this, other)| {
+                (*other != Preference::L || *this == Preference::L)
+                    && (*this != Preference::W || *other == Preference::W)
+            })
+    }
+
+    // e.g. "Onset or Max >> MaxFinalV, Dep": one of the constraints preferring the winner has to
+    // outrank all of the ones preferring the loser
+    pub fn display(&self, names: &[String]) -> String {
+        let with = |preference: Preference| -> Vec<String> {
+            self.preferences
+                .iter()
+                .zip(names)
+                .filter(|(other, _)| **other == preference)
+                .map(|(_, name)| name.to_owned())
+                .collect()
+        };
+
+        format!(
+            "{} >> {}",
+            with(Preference::W).join(" or "),
+            with(Preference::L).join(", ")
+        )
+    }
+}
+
+// an ERC written out the usual way, one W, L or e per constraint, for the learners' tests
+#[cfg(test)]
+pub fn erc(preferences: &str) -> Erc {
+    Erc {
+        winner: String::new(),
+        loser: String::new(),
+        preferences: preferences
+            .chars()
+            .map(|preference| match preference {
+                'W' => Preference::W,
+                'L' => Preference::L,
+                _ => Preference::E,
+            })
+            .collect(),
+    }
+}
+
+// the winner of a tableau against each of its losers; losers that come out the same as each other
+// and violate the same constraints only get one ERC between them
+pub fn ercs(tableau: &Tableau) -> Vec<Erc> {
+    tableau
+        .candidates
+        .iter()
+        .filter(|(form, _)| *form != tableau.winner)
+        .fold(Vec::new(), |mut ercs, (loser, loser_violations)| {
+            let erc = Erc::new(
+                &tableau.winner,
+                loser,
+                &tableau.winner_violations,
+                loser_violations,
+            );
+
+            if !ercs.contains(&erc) {
+                ercs.push(erc);
+            }
+
+            ercs
+        })
+}
+
+// a fusion isn't any one winner against any one loser, so it doesn't have either
+pub fn fuse(ercs: &[&Erc]) -> Erc {
+    let count = ercs.first().map_or(0, |erc| erc.preferences.len());
+
+    Erc {
+        winner: String::new(),
+        loser: String::new(),
+        preferences: (0..count)
+            .map(|constraint| {
+                let preferences = || ercs.iter().map(|erc| erc.preferences[constraint]);
+
+                if preferences().any(|preference| preference == Preference::L) {
+                    Preference::L
+                } else if preferences().any(|preference| preference == Preference::W) {
+                    Preference::W
+                } else {
+                    Preference::E
+                }
+            })
+            .collect(),
+    }
+}
+
+// whether some ranking satisfies every one of a set of ERCs. this is RCD (see rcd.rs) without
+// keeping track of the strata: constraints where the fusion of the ERCs left has no L can go in
+// next, taking care of every ERC with a W on one of them. if we get stuck, the fusion of the ERCs
+// left has no W at all, and nothing could satisfy it
+pub fn consistent(ercs: &[&Erc]) -> bool {
+    // ERCs that are all es hold whatever the ranking
+    let mut left: Vec<&Erc> = ercs
+        .iter()
+        .copied()
+        .filter(|erc| !erc.is_trivial())
+        .collect();
+
+    while !left.is_empty() {
+        let fusion = fuse(&left);
+
+        let next: Vec<&Erc> = left
+            .iter()
+            .copied()
+            .filter(|erc| {
+                !erc.preferences
+                    .iter()
+                    .zip(&fusion.preferences)
+                    .any(|(preference, fused)| {
+                        *preference == Preference::W && *fused != Preference::L
+                    })
+            })
+            .collect();
+
+        if next.len() == left.len() {
+            return false;
+        }
+
+        left = next;
+    }
+
+    true
+}
+
+// a set of ERCs entails another if there's no way to go against it while satisfying them. going
+// against an ERC means ranking one of the constraints preferring its loser over all of the ones
+// preferring its winner, which is an ERC in its own right for each of those constraints
+pub fn set_entails(ercs: &[Erc], other: &Erc) -> bool {
+    (0..other.preferences.len())
+        .filter(|constraint| other.prefers_loser(*constraint))
+        .all(|loser| {
+            let against = Erc {
+                winner: other.loser.to_owned(),
+                loser: other.winner.to_owned(),
+                preferences: (0..other.preferences.len())
+                    .map(|constraint| {
+                        if constraint == loser {
+                            Preference::W
+                        } else if other.prefers_winner(constraint) {
+                            Preference::L
+                        } else {
+                            Preference::E
+                        }
+                    })
+                    .collect(),
+            };
+
+            !consistent(
+                &ercs
+                    .iter()
+                    .chain(std::iter::once(&against))
+                    .collect::<Vec<&Erc>>(),
+            )
+        })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComparativeTableau {
+    pub underlying: String,
+    pub winner: String,
+    // the constraints, in the order the preferences are in
+    pub names: Vec<String>,
+    pub ercs: Vec<Erc>,
+}
+
+impl ComparativeTableau {
+    pub fn new(tableau: &Tableau, names: &[String]) -> ComparativeTableau {
+        ComparativeTableau {
+            underlying: tableau.underlying.to_owned(),
+            winner: tableau.winner.to_owned(),
+            names: names.to_owned(),
+            ercs: ercs(tableau),
+        }
+    }
+}
+
+// e.g.
+//
+//     /raɔgɛdɛ/         Onset  Max
+//     ragɛdɛ ~ raɔgɛdɛ  W      L
+impl std::fmt::Display for ComparativeTableau {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // in graphemes, so that tone marks and tie bars don't throw off the columns
+        let width = |str: &str| str.graphemes(true).count();
+        let pad = |str: &str, to: usize| format!("{}{}", str, " ".repeat(to - width(str)));
+
+        let heading = format!("/{}/", self.underlying);
+        let rows: Vec<String> = self
+            .ercs
+            .iter()
+            .map(|erc| format!("{} ~ {}", erc.winner, erc.loser))
+            .collect();
+
+        let first = rows
+            .iter()
+            .chain(std::iter::once(&heading))
+            .map(|row| width(row))
+            .max()
+            .unwrap_or(0);
+
+        let line = |first_column: &str, columns: Vec<String>| -> String {
+            std::iter::once(pad(first_column, first))
+                .chain(
+                    columns
+                        .iter()
+                        .zip(&self.names)
+                        .map(|(column, name)| pad(column, width(name))),
+                )
+                .collect::<Vec<String>>()
+                .join("  ")
+                .trim_end()
+                .to_owned()
+        };
+
+        writeln!(f, "{}", line(&heading, self.names.to_owned()))?;
+
+        self.ercs.iter().zip(&rows).try_for_each(|(erc, row)| {
+            writeln!(
+                f,
+                "{}",
+                line(
+                    row,
+                    erc.preferences
+                        .iter()
+                        .map(|preference| preference.to_string())
+                        .collect()
+                )
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::learn::test_tableau;
+
+    fn ragede() -> Tableau {
+        test_tableau(
+            "raɔgɛdɛ",
+            "ragɛdɛ",
+            &[
+                ("raɔgɛdɛ", vec![1, 0]),
+                ("ragɛdɛ", vec![0, 1]),
+                ("rɔgɛdɛ", vec![0, 1]),
+                ("rɔgɛdɛ", vec![0, 1]),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_erc_1() {
+        let erc = Erc::new("w", "l", &[0, 1, 2], &[1, 1, 0]);
+
+        assert_eq!(
+            erc.preferences,
+            vec![Preference::W, Preference::E, Preference::L]
+        );
+        assert!(erc.prefers_winner(0));
+        assert!(erc.prefers_loser(2));
+        assert!(!erc.is_trivial());
+        assert!(Erc::new("w", "l", &[1, 2], &[1, 2]).is_trivial());
+    }
+
+    #[test]
+    fn test_ercs_1() {
+        let ercs = ercs(&ragede());
+
+        // the two rɔgɛdɛs only count once
+        assert_eq!(ercs.len(), 2);
+        assert_eq!(ercs[0].loser, "raɔgɛdɛ");
+        assert_eq!(ercs[0].preferences, vec![Preference::W, Preference::L]);
+        assert!(ercs[1].is_trivial());
+    }
+
+    #[test]
+    fn test_entails_1() {
+        assert!(erc("WLe").entails(&erc("WLW")));
+        assert!(erc("WLL").entails(&erc("WLe")));
+        assert!(!erc("WLe").entails(&erc("WLL")));
+        assert!(!erc("WWL").entails(&erc("WeL")));
+    }
+
+    #[test]
+    fn test_fuse_1() {
+        assert_eq!(fuse(&[&erc("WLe"), &erc("eWL")]), erc("WLL"));
+        assert_eq!(fuse(&[&erc("Wee"), &erc("eWe")]), erc("WWe"));
+    }
+
+    #[test]
+    fn test_set_entails_1() {
+        // C0 >> C1 and C1 >> C2 get us C0 >> C2, though neither does on its own
+        let ercs = [erc("WLe"), erc("eWL")];
+
+        assert!(set_entails(&ercs, &erc("WeL")));
+        assert!(!ercs.iter().any(|one| one.entails(&erc("WeL"))));
+        assert!(!set_entails(&ercs, &erc("LeW")));
+        assert!(set_entails(&ercs, &erc("eeW")));
+    }
+
+    #[test]
+    fn test_set_entails_2() {
+        use itertools::Itertools;
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+
+        // against every total ranking of four constraints: an ERC holds under a ranking if its
+        // highest ranked W or L is a W
+        let holds = |erc: &Erc, ranking: &[usize]| {
+            ranking
+                .iter()
+                .map(|constraint| erc.preferences[*constraint])
+                .find(|preference| *preference != Preference::E)
+                .is_none_or(|preference| preference == Preference::W)
+        };
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut random = || -> Erc {
+            erc(&(0..4)
+                .map(|_| ['W', 'L', 'e'][rng.gen_range(0..3)])
+                .collect::<String>())
+        };
+
+        (0..500).for_each(|_| {
+            let ercs: Vec<Erc> = (0..3).map(|_| random()).collect();
+            let other = random();
+
+            let rankings: Vec<Vec<usize>> = (0..4)
+                .permutations(4)
+                .filter(|ranking| ercs.iter().all(|erc| holds(erc, ranking)))
+                .collect();
+
+            assert_eq!(
+                consistent(&ercs.iter().collect::<Vec<&Erc>>()),
+                !rankings.is_empty()
+            );
+
+            // inconsistent sets entail anything
+            if !rankings.is_empty() {
+                assert_eq!(
+                    set_entails(&ercs, &other),
+                    rankings.iter().all(|ranking| holds(&other, ranking)),
+                    "{:?} {:?}",
+                    ercs,
+                    other
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn test_display_1() {
+        let comparative =
+            ComparativeTableau::new(&ragede(), &["Onset".to_owned(), "Max".to_owned()]);
+
+        assert_eq!(
+            comparative.to_string(),
+            "/raɔgɛdɛ/         Onset  Max\n\
+             ragɛdɛ ~ raɔgɛdɛ  W      L\n\
+             ragɛdɛ ~ rɔgɛdɛ   e      e\n"
+        );
+    }
+}
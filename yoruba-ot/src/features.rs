@@ -0,0 +1,196 @@
+use crate::inventory::inventory;
+
+// a distinctive feature matrix for segments, so that constraints and GEN can talk about natural
+// classes like [+high, -back] instead of listing out characters; the features of each segment
+// come from the inventory (see inventory.rs)
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Feature {
+    Syllabic,
+    Consonantal,
+    Sonorant,
+    Continuant,
+    Nasal,
+    Voice,
+    High,
+    Low,
+    Back,
+    Round,
+    Atr,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Place {
+    Labial,
+    Coronal,
+    Dorsal,
+    LabialVelar,
+    Glottal,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Features {
+    pub syllabic: bool,
+    pub consonantal: bool,
+    pub sonorant: bool,
+    pub continuant: bool,
+    pub nasal: bool,
+    pub voice: bool,
+    pub high: bool,
+    pub low: bool,
+    pub back: bool,
+    pub round: bool,
+    pub atr: bool,
+    // vowels have no place of articulation here; their backness and rounding do that job
+    pub place: Option<Place>,
+}
+
+impl Features {
+    pub fn get(&self, feature: Feature) -> bool {
+        match feature {
+            Feature::Syllabic => self.syllabic,
+            Feature::Consonantal => self.consonantal,
+            Feature::Sonorant => self.sonorant,
+            Feature::Continuant => self.continuant,
+            Feature::Nasal => self.nasal,
+            Feature::Voice => self.voice,
+            Feature::High => self.high,
+            Feature::Low => self.low,
+            Feature::Back => self.back,
+            Feature::Round => self.round,
+            Feature::Atr => self.atr,
+        }
+    }
+
+    pub fn set(&mut self, feature: Feature, value: bool) {
+        match feature {
+            Feature::Syllabic => self.syllabic = value,
+            Feature::Consonantal => self.consonantal = value,
+            Feature::Sonorant => self.sonorant = value,
+            Feature::Continuant => self.continuant = value,
+            Feature::Nasal => self.nasal = value,
+            Feature::Voice => self.voice = value,
+            Feature::High => self.high = value,
+            Feature::Low => self.low = value,
+            Feature::Back => self.back = value,
+            Feature::Round => self.round = value,
+            Feature::Atr => self.atr = value,
+        }
+    }
+}
+
+pub const PLACE_NAMES: [(&str, Place); 5] = [
+    ("labial", Place::Labial),
+    ("coronal", Place::Coronal),
+    ("dorsal", Place::Dorsal),
+    ("labial-velar", Place::LabialVelar),
+    ("glottal", Place::Glottal),
+];
+
+// a natural class, written as a list of feature values: [+high, -back] is
+// NaturalClass(vec![(true, Feature::High), (false, Feature::Back)])
+#[derive(Debug, Clone, PartialEq)]
+pub struct NaturalClass(pub Vec<(bool, Feature)>);
+
+pub const FEATURE_NAMES: [(&str, Feature); 11] = [
+    ("syllabic", Feature::Syllabic),
+    ("consonantal", Feature::Consonantal),
+    ("sonorant", Feature::Sonorant),
+    ("continuant", Feature::Continuant),
+    ("nasal", Feature::Nasal),
+    ("voice", Feature::Voice),
+    ("high", Feature::High),
+    ("low", Feature::Low),
+    ("back", Feature::Back),
+    ("round", Feature::Round),
+    ("atr", Feature::Atr),
+];
+
+impl NaturalClass {
+    // reads a class written out like "+high,-back"
+    pub fn parse(spec: &str) -> Option<NaturalClass> {
+        spec.split(',')
+            .map(|value| {
+                let (sign, name) = value
+                    .trim()
+                    .split_at(value.trim().find(char::is_alphabetic)?);
+
+                let feature = FEATURE_NAMES
+                    .iter()
+                    .find(|(feature_name, _)| *feature_name == name)?
+                    .1;
+
+                match sign {
+                    "+" => Some((true, feature)),
+                    "-" => Some((false, feature)),
+                    _ => None,
+                }
+            })
+            .collect::<Option<Vec<(bool, Feature)>>>()
+            .map(NaturalClass)
+    }
+
+    pub fn contains(&self, features: &Features) -> bool {
+        self.0
+            .iter()
+            .all(|(value, feature)| features.get(*feature) == *value)
+    }
+
+    // every segment in the inventory that belongs to this class
+    pub fn segments(&self) -> Vec<String> {
+        inventory()
+            .segments
+            .iter()
+            .filter(|seg| self.contains(&seg.features))
+            .map(|seg| seg.symbol.to_owned())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_natural_class_1() {
+        let high_front = NaturalClass(vec![
+            (true, Feature::Syllabic),
+            (true, Feature::High),
+            (false, Feature::Back),
+        ]);
+
+        assert_eq!(high_front.segments(), vec!["i"]);
+    }
+
+    #[test]
+    fn test_natural_class_2() {
+        let vowels = NaturalClass(vec![(true, Feature::Syllabic)]);
+
+        assert_eq!(vowels.segments(), vec!["o", "ɛ", "ɔ", "i", "u", "a", "e"]);
+    }
+
+    #[test]
+    fn test_natural_class_parse_1() {
+        assert_eq!(
+            NaturalClass::parse("+high, -back"),
+            Some(NaturalClass(vec![
+                (true, Feature::High),
+                (false, Feature::Back)
+            ]))
+        );
+        assert_eq!(NaturalClass::parse("high"), None);
+        assert_eq!(NaturalClass::parse("+tall"), None);
+    }
+
+    #[test]
+    fn test_natural_class_3() {
+        let voiced_stops = NaturalClass(vec![
+            (true, Feature::Consonantal),
+            (false, Feature::Continuant),
+            (false, Feature::Sonorant),
+            (true, Feature::Voice),
+        ]);
+
+        assert_eq!(voiced_stops.segments(), vec!["b", "d", "dʒ", "g", "gb"]);
+    }
+}
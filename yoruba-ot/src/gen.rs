@@ -0,0 +1,133 @@
+use crate::features::{Feature, NaturalClass};
+use crate::utils::{permute_insert, permute_replace, permute_swap};
+use crate::{get_seg_type, Segment, SyllableIndex, UnderlyingIndex};
+
+// the operations GEN applies on top of deletion, which is always performed; everything here is
+// off by default, since each operation multiplies the size of the candidate set
+#[derive(Debug, Clone, Default)]
+pub struct Gen {
+    pub epenthesis: Option<Epenthesis>,
+    pub substitution: Option<Substitution>,
+    pub metathesis: Option<Metathesis>,
+    // whether the tone of a deleted vowel may float or reassociate rather than being deleted too
+    pub floating_tones: bool,
+}
+
+// segments that may be epenthesized, and how many of them may be inserted into a single candidate
+#[derive(Debug, Clone, PartialEq)]
+pub struct Epenthesis {
+    pub segments: Vec<String>,
+    pub max: usize,
+}
+
+// segments that underlying segments may change into, and how many may change in a single
+// candidate; a segment only ever changes into another segment of the same type
+#[derive(Debug, Clone, PartialEq)]
+pub struct Substitution {
+    pub segments: Vec<String>,
+    pub max: usize,
+}
+
+// how many pairs of adjacent segments may swap places in a single candidate
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metathesis {
+    pub max: usize,
+}
+
+impl Gen {
+    // all forms derivable from an (already deletion-permuted) form by the enabled operations,
+    // starting with the form itself
+    pub fn apply(&self, form: &[Segment]) -> Vec<Vec<Segment>> {
+        // substitution goes first so that we never bother changing an epenthetic segment
+        let substituted = match &self.substitution {
+            Some(substitution) => permute_replace(
+                form,
+                &|seg: &Segment| substitution.replacements(seg),
+                substitution.max,
+            ),
+            None => vec![form.to_vec()],
+        };
+
+        let metathesized: Vec<Vec<Segment>> = substituted
+            .iter()
+            .flat_map(|form| match &self.metathesis {
+                Some(metathesis) => permute_swap(form, metathesis.max),
+                None => vec![form.to_vec()],
+            })
+            .collect();
+
+        metathesized
+            .iter()
+            .flat_map(|form| match &self.epenthesis {
+                Some(epenthesis) => permute_insert(form, &epenthesis.inventory(), epenthesis.max),
+                None => vec![form.to_vec()],
+            })
+            .collect()
+    }
+}
+
+impl Epenthesis {
+    fn inventory(&self) -> Vec<Segment> {
+        self.segments
+            .iter()
+            .map(|seg| Segment {
+                char: seg.to_owned(),
+                syllable_index: SyllableIndex::None,
+                seg_type: get_seg_type(seg),
+                // epenthetic segments don't belong to any underlying morpheme
+                morpheme_index: UnderlyingIndex::None,
+                correspondent: None,
+            })
+            .collect()
+    }
+}
+
+// nothing to change into yet, and one segment changed per candidate
+impl Default for Substitution {
+    fn default() -> Self {
+        Substitution {
+            segments: Vec::new(),
+            max: 1,
+        }
+    }
+}
+
+impl Substitution {
+    // more segments to change into, leaving out any that are already there
+    pub fn add(&mut self, segments: impl IntoIterator<Item = String>) {
+        segments.into_iter().for_each(|seg| {
+            if !self.segments.contains(&seg) {
+                self.segments.push(seg)
+            }
+        })
+    }
+
+    // segments may change into any member of a natural class
+    pub fn natural_class(class: &NaturalClass, max: usize) -> Substitution {
+        Substitution {
+            segments: class.segments().iter().map(|seg| seg.to_string()).collect(),
+            max,
+        }
+    }
+
+    // vowel assimilation: any vowel may become any other vowel
+    pub fn vowels(max: usize) -> Substitution {
+        Substitution::natural_class(&NaturalClass(vec![(true, Feature::Syllabic)]), max)
+    }
+
+    fn replacements(&self, seg: &Segment) -> Vec<Segment> {
+        self.segments
+            .iter()
+            .filter(|replacement| {
+                **replacement != seg.char && get_seg_type(replacement) == seg.seg_type
+            })
+            .map(|replacement| Segment {
+                char: replacement.to_owned(),
+                syllable_index: seg.syllable_index.clone(),
+                seg_type: seg.seg_type.clone(),
+                morpheme_index: seg.morpheme_index.clone(),
+                correspondent: seg.correspondent,
+            })
+            .collect()
+    }
+}
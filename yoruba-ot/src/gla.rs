@@ -0,0 +1,167 @@
+use crate::corpus::Datum;
+use crate::gen::Gen;
+use crate::learn::{tableaux, ConstraintSet, LearningError, Tableau};
+use crate::stochastic::{sample_ranking, winners};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+// the Gradual Learning Algorithm (Boersma & Hayes): learns Stochastic OT ranking values (see
+// stochastic.rs) one datum at a time. the learner evaluates each underlying form with its current
+// grammar, noise and all; whenever it comes up with something other than what was observed, every
+// constraint preferring the observed form gets nudged up the scale, and every constraint preferring
+// the learner's own form gets nudged down
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlaSettings {
+    // where every constraint starts out on the ranking scale
+    pub initial_ranking_value: f64,
+    // the noise to evaluate with while learning
+    pub noise: f64,
+    // how far a constraint gets nudged on an error
+    pub plasticity: f64,
+    // how many passes to make over the data
+    pub epochs: usize,
+}
+
+impl Default for GlaSettings {
+    fn default() -> Self {
+        GlaSettings {
+            initial_ranking_value: 100.0,
+            noise: 2.0,
+            plasticity: 1.0,
+            epochs: 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlaResult {
+    // one per constraint, in the order of the constraint set
+    pub ranking_values: Vec<f64>,
+    // the share of the data the learner got wrong in each epoch, as it went
+    pub learning_curve: Vec<f64>,
+}
+
+// the GLA over tableaux that have already been built; every observation of every datum is learned
+// from once an epoch, in a different order each time
+pub fn learn(tableaux: &[Tableau], settings: &GlaSettings, rng: &mut impl Rng) -> GlaResult {
+    let constraint_count = tableaux
+        .first()
+        .map_or(0, |tableau| tableau.winner_violations.len());
+
+    let mut ranking_values = vec![settings.initial_ranking_value; constraint_count];
+    let mut learning_curve: Vec<f64> = Vec::new();
+    let mut order: Vec<&Tableau> = tableaux
+        .iter()
+        .flat_map(|tableau| std::iter::repeat_n(tableau, tableau.frequency))
+        .collect();
+
+    (0..settings.epochs).for_each(|_| {
+        order.shuffle(rng);
+
+        let errors = order
+            .iter()
+            .filter(|tableau| {
+                let profiles: Vec<Vec<usize>> = tableau
+                    .candidates
+                    .iter()
+                    .map(|(_, violations)| violations.to_owned())
+                    .collect();
+
+                let winners = winners(
+                    &profiles,
+                    &sample_ranking(&ranking_values, settings.noise, rng),
+                );
+
+                let (output, output_violations) =
+                    &tableau.candidates[winners[rng.gen_range(0..winners.len())]];
+
+                if *output == tableau.winner {
+                    return false;
+                }
+
+                ranking_values
+                    .iter_mut()
+                    .enumerate()
+                    .for_each(|(constraint, ranking_value)| {
+                        let winner = tableau.winner_violations[constraint];
+                        let output = output_violations[constraint];
+
+                        if winner < output {
+                            *ranking_value += settings.plasticity;
+                        } else if winner > output {
+                            *ranking_value -= settings.plasticity;
+                        }
+                    });
+
+                true
+            })
+            .count();
+
+        learning_curve.push(errors as f64 / order.len().max(1) as f64);
+    });
+
+    GlaResult {
+        ranking_values,
+        learning_curve,
+    }
+}
+
+pub fn gla(
+    data: &[Datum],
+    constraints: ConstraintSet,
+    gen: &Gen,
+    settings: &GlaSettings,
+    rng: &mut impl Rng,
+) -> Result<GlaResult, LearningError> {
+    Ok(learn(&tableaux(data, constraints, gen)?, settings, rng))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::learn::test_tableau;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    // a winner w against a loser l
+    fn tableau(winner_violations: Vec<usize>, loser_violations: Vec<usize>) -> Tableau {
+        test_tableau(
+            "u",
+            "w",
+            &[("w", winner_violations), ("l", loser_violations)],
+        )
+    }
+
+    #[test]
+    fn test_learn_1() {
+        // C1 has to come to outrank C0, and C2 never matters
+        let result = learn(
+            &[tableau(vec![1, 0, 0], vec![0, 1, 0])],
+            &GlaSettings::default(),
+            &mut StdRng::seed_from_u64(0),
+        );
+
+        assert!(result.ranking_values[1] - result.ranking_values[0] > 5.0);
+        assert_eq!(result.ranking_values[2], 100.0);
+        // the two move by the same amount in opposite directions
+        assert!((result.ranking_values[0] + result.ranking_values[1] - 200.0).abs() < 1e-9);
+        // wrong every so often to start with, and not any more by the end
+        let curve = &result.learning_curve;
+        assert!(curve[..20].iter().sum::<f64>() > 0.0);
+        assert_eq!(curve[curve.len() - 20..].iter().sum::<f64>(), 0.0);
+    }
+
+    #[test]
+    fn test_learn_2() {
+        // the learner can't get this wrong, so nothing moves
+        let result = learn(
+            &[tableau(vec![0, 0], vec![1, 1])],
+            &GlaSettings::default(),
+            &mut StdRng::seed_from_u64(0),
+        );
+
+        assert_eq!(result.ranking_values, vec![100.0, 100.0]);
+        assert!(result.learning_curve.iter().all(|errors| *errors == 0.0));
+    }
+}
@@ -0,0 +1,114 @@
+use crate::constraint::Constraint;
+use crate::gen::Gen;
+use crate::SyllabifiedCandidate;
+
+// Harmonic Grammar: instead of being ranked, constraints get weights, and the winner is whichever
+// candidate has the lowest weighted sum of violations; lower constraints can gang up to beat a
+// higher one, which ranking can't do
+
+// penalties closer together than this are ties; they're sums of floats, so they won't always come
+// out exactly equal
+const TOLERANCE: f64 = 1e-9;
+
+#[derive(Debug)]
+pub struct WeightedConstraint {
+    pub weight: f64,
+    pub constraint: Box<dyn Constraint>,
+}
+
+// the weighted sum of a candidate's violations; this is harmony with the sign flipped
+pub fn penalty(constraints: &[WeightedConstraint], surface: &SyllabifiedCandidate) -> f64 {
+    constraints
+        .iter()
+        .map(|constraint| {
+            constraint.weight * constraint.constraint.evaluate(surface.to_owned()) as f64
+        })
+        .sum()
+}
+
+pub fn evaluate_hg(
+    underlying_candidate: SyllabifiedCandidate,
+    constraints: Vec<WeightedConstraint>,
+    gen: &Gen,
+) -> Vec<SyllabifiedCandidate> {
+    let penalties: Vec<(SyllabifiedCandidate, f64)> = underlying_candidate
+        .permute(gen)
+        .into_iter()
+        .map(|form| {
+            let penalty = penalty(&constraints, &form);
+            (form, penalty)
+        })
+        .collect();
+
+    let min = penalties
+        .iter()
+        .map(|(_, penalty)| *penalty)
+        .fold(f64::INFINITY, f64::min);
+
+    penalties
+        .into_iter()
+        .filter(|(_, penalty)| *penalty - min < TOLERANCE)
+        .map(|(form, _)| form)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::constraint::{Dep, UnweightedMax, UnweightedOnset};
+
+    #[test]
+    fn test_penalty_1() {
+        let cand: SyllabifiedCandidate = "owo-ki-owo".parse().unwrap();
+        let constraints = vec![
+            WeightedConstraint {
+                weight: 2.5,
+                constraint: Box::new(UnweightedOnset),
+            },
+            WeightedConstraint {
+                weight: 10.0,
+                constraint: Box::new(Dep(cand.clone())),
+            },
+        ];
+
+        // two onsetless syllables, no epenthesis
+        assert_eq!(penalty(&constraints, &cand), 5.0);
+    }
+
+    fn onset_against_max(max_weight: f64) -> Vec<String> {
+        let cand: SyllabifiedCandidate = "owo-ki-owo".parse().unwrap();
+
+        evaluate_hg(
+            cand.clone(),
+            vec![
+                WeightedConstraint {
+                    weight: 1.0,
+                    constraint: Box::new(UnweightedOnset),
+                },
+                WeightedConstraint {
+                    weight: max_weight,
+                    constraint: Box::new(UnweightedMax(cand)),
+                },
+            ],
+            &Gen::default(),
+        )
+        .into_iter()
+        .map(String::from)
+        .collect()
+    }
+
+    #[test]
+    fn test_evaluate_hg_1() {
+        assert_eq!(onset_against_max(2.0), vec!["owokiowo"]);
+        assert_eq!(onset_against_max(0.5), vec!["wokiwo", "wokowo"]);
+    }
+
+    #[test]
+    fn test_evaluate_hg_2() {
+        // each deletion trades one violation for another, so nothing beats anything else
+        assert_eq!(
+            onset_against_max(1.0),
+            vec!["owokiowo", "owokiwo", "owokowo", "wokiowo", "wokiwo", "wokowo"]
+        );
+    }
+}
@@ -0,0 +1,214 @@
+use crate::features::{Features, FEATURE_NAMES, PLACE_NAMES};
+use once_cell::sync::OnceCell;
+use unicode_segmentation::UnicodeSegmentation;
+
+// the inventory we fall back on if none is loaded at startup
+const YORUBA: &str = include_str!("../inventories/yoruba.txt");
+
+static INVENTORY: OnceCell<Inventory> = OnceCell::new();
+
+// combining double inverted breve and double breve below, as in d͡ʒ; these only say that the
+// symbols on either side are one segment, so we ignore them when looking segments up
+const TIE_BARS: [char; 2] = ['\u{361}', '\u{35c}'];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inventory {
+    pub segments: Vec<InventorySegment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InventorySegment {
+    pub symbol: String,
+    pub sonority: usize,
+    pub features: Features,
+}
+
+// the inventory in use; this is Yoruba unless another one was loaded
+pub fn inventory() -> &'static Inventory {
+    INVENTORY.get_or_init(|| Inventory::parse(YORUBA).expect("The Yoruba inventory is malformed!"))
+}
+
+// swaps out the default inventory for the one in the given file; this has to happen before any
+// words are parsed, since those need the inventory
+pub fn load_inventory(path: &str) -> Result<(), String> {
+    let inventory = Inventory::parse(
+        &std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?,
+    )
+    .map_err(|err| format!("{}: {}", path, err))?;
+
+    INVENTORY
+        .set(inventory)
+        .map_err(|_| "An inventory is already in use!".to_owned())
+}
+
+impl Inventory {
+    // see inventories/yoruba.txt for the format
+    pub fn parse(str: &str) -> Result<Inventory, String> {
+        let segments = str
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.split('#').next().unwrap().trim()))
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(line_number, line)| {
+                parse_segment(line).map_err(|err| format!("line {}: {}", line_number, err))
+            })
+            .collect::<Result<Vec<InventorySegment>, String>>()?;
+
+        Ok(Inventory { segments })
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<&InventorySegment> {
+        let symbol = symbol.replace(TIE_BARS, "");
+
+        self.segments.iter().find(|seg| seg.symbol == symbol)
+    }
+
+    // splits a word up into segments, taking the longest segment in the inventory at each point,
+    // so that multigraphs like gb, kp and dʒ (or d͡ʒ) come out whole, even when they're next to
+    // each other as in gbgb; anything else is left as a single grapheme
+    pub fn tokenize(&self, str: &str) -> Vec<String> {
+        let graphemes: Vec<&str> = str.graphemes(true).collect();
+
+        let longest = self
+            .segments
+            .iter()
+            .map(|seg| seg.symbol.graphemes(true).count())
+            .max()
+            .unwrap_or(1);
+
+        let mut segments = Vec::new();
+        let mut start = 0;
+
+        while start < graphemes.len() {
+            let length = (2..=longest.min(graphemes.len() - start))
+                .rev()
+                .find(|length| {
+                    self.get(&graphemes[start..start + length].concat())
+                        .is_some()
+                })
+                .unwrap_or(1);
+
+            segments.push(graphemes[start..start + length].concat());
+            start += length;
+        }
+
+        segments
+    }
+}
+
+fn parse_segment(line: &str) -> Result<InventorySegment, String> {
+    let mut fields = line.split_whitespace();
+
+    let symbol = fields.next().ok_or("missing segment")?.to_owned();
+
+    let sonority = fields
+        .next()
+        .ok_or(format!("missing sonority for {}", symbol))?
+        .parse()
+        .map_err(|_| format!("sonority of {} isn't a number", symbol))?;
+
+    let features = fields.try_fold(Features::default(), |mut features, field| {
+        if let Some((_, place)) = PLACE_NAMES.iter().find(|(name, _)| *name == field) {
+            features.place = Some(*place);
+            return Ok(features);
+        }
+
+        let (value, name) = match field.split_at(field.find(char::is_alphabetic).unwrap_or(0)) {
+            ("+", name) => (true, name),
+            ("-", name) => (false, name),
+            _ => return Err(format!("can't read {} as a feature", field)),
+        };
+
+        let (_, feature) = FEATURE_NAMES
+            .iter()
+            .find(|(feature_name, _)| *feature_name == name)
+            .ok_or(format!("unknown feature {}", name))?;
+
+        features.set(*feature, value);
+
+        Ok(features)
+    })?;
+
+    Ok(InventorySegment {
+        symbol,
+        sonority,
+        features,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::features::Place;
+
+    #[test]
+    fn test_inventory_1() {
+        let inventory = Inventory::parse(
+            "# a comment
+            i 3 +syllabic +high # another one
+
+            kp 0 +consonantal -voice labial-velar",
+        )
+        .unwrap();
+
+        assert_eq!(inventory.segments.len(), 2);
+        assert_eq!(inventory.get("i").unwrap().sonority, 3);
+        assert!(inventory.get("i").unwrap().features.high);
+        assert!(!inventory.get("i").unwrap().features.back);
+        assert_eq!(
+            inventory.get("kp").unwrap().features.place,
+            Some(Place::LabialVelar)
+        );
+    }
+
+    #[test]
+    fn test_inventory_2() {
+        assert_eq!(
+            Inventory::parse("i 3 +syllabic\nu x +syllabic"),
+            Err("line 2: sonority of u isn't a number".to_owned())
+        );
+        assert_eq!(
+            Inventory::parse("i 3 +tall"),
+            Err("line 1: unknown feature tall".to_owned())
+        );
+        assert_eq!(
+            Inventory::parse("i 3 high"),
+            Err("line 1: can't read high as a feature".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_tokenize_1() {
+        let inventory = Inventory::parse(YORUBA).unwrap();
+
+        assert_eq!(inventory.tokenize("gbgb"), vec!["gb", "gb"]);
+        assert_eq!(inventory.tokenize("ggb"), vec!["g", "gb"]);
+        assert_eq!(inventory.tokenize("kpadʒa"), vec!["kp", "a", "dʒ", "a"]);
+        assert_eq!(inventory.tokenize("d͡ʒɔ̀"), vec!["d͡ʒ", "ɔ̀"]);
+        assert_eq!(inventory.tokenize("g-b"), vec!["g", "-", "b"]);
+        assert_eq!(inventory.tokenize(""), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_tokenize_2() {
+        // longer multigraphs win over the shorter ones inside them
+        let inventory = Inventory::parse(
+            "t 0 +consonantal
+            tʃ 0 +consonantal
+            tʃʼ 0 +consonantal
+            ʃ 0 +consonantal
+            a 4 +syllabic",
+        )
+        .unwrap();
+
+        assert_eq!(inventory.tokenize("tʃʼatʃa"), vec!["tʃʼ", "a", "tʃ", "a"]);
+        assert_eq!(inventory.tokenize("t͡ʃa"), vec!["t͡ʃ", "a"]);
+        assert_eq!(inventory.get("t͡ʃ"), inventory.get("tʃ"));
+    }
+
+    #[test]
+    fn test_inventory_3() {
+        // the default inventory has everything our tests use
+        assert!(Inventory::parse(YORUBA).unwrap().segments.len() >= 26);
+    }
+}
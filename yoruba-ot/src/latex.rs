@@ -0,0 +1,224 @@
+use crate::tableau::ViolationTableau;
+use crate::tone::{split_tones, ToneLevel};
+use unicode_segmentation::UnicodeSegmentation;
+
+// violation tableaux (see tableau.rs) as LaTeX, for the ot-tableau package the paper uses, e.g.
+//
+//     \begin{tableau}{c|c}
+//         \inp{\ips{ra-o}}          \const{\textsc{Onset}} \const{\textsc{Max}}
+//         \cand[\Optimal]{\ips{ra}} \vio{}                 \vio{*}
+//         \cand{\ips{rao}}          \vio{*!}               \vio{}
+//     \end{tableau}
+//
+// ot-tableau sets the forms in IPA with tipa, which has its own ASCII spelling for most of the
+// symbols (E for ɛ, \'a for á, and so on), so forms can be transliterated into that and set in
+// \ips; otherwise they go in as they are, which only works with a Unicode IPA font. shading is
+// left to ot-tableau, which does it by itself going by where the !s are
+
+// IPA symbols and how tipa spells them; anything not in here is spelled the same in both
+const TIPA: [(&str, &str); 13] = [
+    ("ɛ", "E"),
+    ("ɔ", "O"),
+    ("ə", "@"),
+    ("ɪ", "I"),
+    ("ʊ", "U"),
+    ("ɑ", "A"),
+    ("ʃ", "S"),
+    ("ʒ", "Z"),
+    ("ŋ", "N"),
+    ("ɾ", "R"),
+    ("ɡ", "g"),
+    ("ɲ", "\\textltailn{}"),
+    ("∅", "$\\emptyset$"),
+];
+
+// the accents tipa puts tones on with
+fn accent(level: &ToneLevel) -> &'static str {
+    match level {
+        ToneLevel::High => "\\'",
+        ToneLevel::Mid => "\\=",
+        ToneLevel::Low => "\\`",
+    }
+}
+
+// e.g. "dʒɛ̀-edé" -> "\textdyoghlig{}\`E-ed\'e"
+pub fn tipa(ipa: &str) -> String {
+    ipa.graphemes(true)
+        .map(|grapheme| {
+            let (base, levels) = split_tones(grapheme);
+
+            // tie bars are what makes dʒ and tʃ single symbols in IPA; in tipa that's the
+            // ligatures below
+            let base: String = base
+                .chars()
+                .filter(|char| *char != '\u{361}')
+                .map(|char| {
+                    TIPA.iter()
+                        .find(|(symbol, _)| *symbol == char.to_string())
+                        .map_or(char.to_string(), |(_, tipa)| tipa.to_string())
+                })
+                .collect();
+
+            levels.iter().fold(base, |base, level| {
+                if base.chars().count() == 1 {
+                    format!("{}{}", accent(level), base)
+                } else {
+                    format!("{}{{{}}}", accent(level), base)
+                }
+            })
+        })
+        .collect::<String>()
+        .replace("dZ", "\\textdyoghlig{}")
+        .replace("tS", "\\textteshlig{}")
+}
+
+// constraint names go in as text, where these mean something to LaTeX
+fn escape(name: &str) -> String {
+    name.chars()
+        .map(|char| match char {
+            '\\' => "\\textbackslash{}".to_owned(),
+            '~' => "\\textasciitilde{}".to_owned(),
+            '^' => "\\textasciicircum{}".to_owned(),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => format!("\\{}", char),
+            _ => char.to_string(),
+        })
+        .collect()
+}
+
+pub fn latex(tableau: &ViolationTableau, transliterate: bool) -> String {
+    let form = |form: &str| {
+        if transliterate {
+            format!("\\ips{{{}}}", tipa(form))
+        } else {
+            form.to_owned()
+        }
+    };
+
+    // the constraints in a stratum are split up by dashed lines, and the strata by solid ones
+    let columns = tableau
+        .strata
+        .iter()
+        .map(|stratum| vec!["c"; stratum.len()].join(":"))
+        .collect::<Vec<String>>()
+        .join("|");
+
+    let lines: Vec<Vec<String>> = std::iter::once(
+        std::iter::once(format!("\\inp{{{}}}", form(&tableau.underlying)))
+            .chain(
+                tableau
+                    .strata
+                    .iter()
+                    .flatten()
+                    .map(|name| format!("\\const{{\\textsc{{{}}}}}", escape(name))),
+            )
+            .collect(),
+    )
+    .chain(tableau.rows.iter().map(|row| {
+        std::iter::once(format!(
+            "\\cand{}{{{}}}",
+            if row.winner { "[\\Optimal]" } else { "" },
+            form(&row.form)
+        ))
+        .chain(
+            row.cells
+                .iter()
+                .flatten()
+                .map(|cell| format!("\\vio{{{}}}", cell.marks())),
+        )
+        .collect()
+    }))
+    .collect();
+
+    // lined up the way the ones in the paper are typed, so that they're easy to edit by hand
+    let widths: Vec<usize> = (0..lines[0].len())
+        .map(|column| {
+            lines
+                .iter()
+                .map(|line| line[column].graphemes(true).count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let body: String = lines
+        .iter()
+        .map(|line| {
+            let line = line
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| {
+                    format!(
+                        "{}{}",
+                        cell,
+                        " ".repeat(width - cell.graphemes(true).count())
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join(" ");
+
+            format!("    {}\n", line.trim_end())
+        })
+        .collect();
+
+    format!(
+        "\\begin{{tableau}}{{{}}}\n{}\\end{{tableau}}\n",
+        columns, body
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tableau::{Cell, Row};
+
+    #[test]
+    fn test_tipa_1() {
+        assert_eq!(tipa("dʒɛ̀-edé"), "\\textdyoghlig{}\\`E-ed\\'e");
+        assert_eq!(tipa("d͡ʒɔ́"), "\\textdyoghlig{}\\'O");
+        assert_eq!(tipa("gbaʃɛ"), "gbaSE");
+        // a falling tone, and a tone on something tipa spells with more than one character
+        assert_eq!(tipa("ɛ\u{301}\u{300}"), "\\`{\\'E}");
+        assert_eq!(tipa("ɲ\u{301}"), "\\'{\\textltailn{}}");
+    }
+
+    #[test]
+    fn test_latex_1() {
+        let cell = |violations: usize, fatal: Option<usize>| Cell {
+            violations,
+            fatal,
+            shaded: false,
+        };
+
+        let tableau = ViolationTableau {
+            underlying: "dʒɛ-ede".to_owned(),
+            strata: vec![
+                vec!["Onset".to_owned(), "*Float".to_owned()],
+                vec!["Max_C".to_owned()],
+            ],
+            rows: vec![
+                Row {
+                    form: "dʒede".to_owned(),
+                    winner: true,
+                    cells: vec![vec![cell(0, None), cell(0, None)], vec![cell(1, None)]],
+                },
+                Row {
+                    form: "dʒɛede".to_owned(),
+                    winner: false,
+                    cells: vec![vec![cell(1, Some(1)), cell(0, None)], vec![cell(0, None)]],
+                },
+            ],
+        };
+
+        assert_eq!(
+            latex(&tableau, true),
+            "\\begin{tableau}{c:c|c}\n    \
+             \\inp{\\ips{\\textdyoghlig{}E-ede}}          \\const{\\textsc{Onset}} \\const{\\textsc{*Float}} \\const{\\textsc{Max\\_C}}\n    \
+             \\cand[\\Optimal]{\\ips{\\textdyoghlig{}ede}} \\vio{}                 \\vio{}                  \\vio{*}\n    \
+             \\cand{\\ips{\\textdyoghlig{}Eede}}          \\vio{*!}               \\vio{}                  \\vio{}\n\
+             \\end{tableau}\n"
+        );
+        // without tipa, the forms go in as they are, with no \ips around them
+        assert!(latex(&tableau, false).contains("\\inp{dʒɛ-ede}"));
+        assert!(latex(&tableau, false).contains("\\cand{dʒɛede}"));
+    }
+}
@@ -0,0 +1,170 @@
+use crate::constraint::{Constraint, ConstraintKind};
+use crate::corpus::Datum;
+use crate::gen::Gen;
+use crate::SyllabifiedCandidate;
+
+// what the learners (see rcd.rs, gla.rs and sgd.rs) have in common: the constraint set they learn over,
+// and tableaux built from the training data
+
+// the constraints to learn over, given an underlying form; faithfulness constraints need that
+// form, so the constraints get built over again for every datum, but they have to come back in
+// the same order every time
+pub type ConstraintSet = fn(&SyllabifiedCandidate) -> Vec<Box<dyn Constraint>>;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Learner {
+    // Recursive Constraint Demotion
+    #[default]
+    Rcd,
+    // Biased Constraint Demotion
+    Bcd,
+    // the Gradual Learning Algorithm
+    Gla,
+    // MaxEnt weights, by stochastic gradient descent
+    MaxEnt,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum LearningError {
+    // GEN never comes up with the observed surface form
+    WinnerNotGenerated { underlying: String, winner: String },
+    // no ranking makes every winner beat all of its losers; these are the constraints that
+    // couldn't be ranked
+    Inconsistent { unranked: Vec<String> },
+}
+
+impl std::fmt::Display for LearningError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LearningError::WinnerNotGenerated { underlying, winner } => {
+                write!(f, "GEN doesn't come up with {} for {}", winner, underlying)
+            }
+            LearningError::Inconsistent { unranked } => write!(
+                f,
+                "the data are inconsistent; couldn't rank {}",
+                unranked.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LearningError {}
+
+pub fn constraint_names(constraints: ConstraintSet) -> Vec<String> {
+    constraints(&SyllabifiedCandidate {
+        form: vec![],
+        tones: vec![],
+    })
+    .iter()
+    .map(|constraint| constraint.name())
+    .collect()
+}
+
+pub fn constraint_kinds(constraints: ConstraintSet) -> Vec<ConstraintKind> {
+    constraints(&SyllabifiedCandidate {
+        form: vec![],
+        tones: vec![],
+    })
+    .iter()
+    .map(|constraint| constraint.kind())
+    .collect()
+}
+
+// one datum's underlying form, its observed winner, and every candidate GEN comes up with for it
+// (the winner included), with how many times each of them violates each constraint
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tableau {
+    pub underlying: String,
+    pub winner: String,
+    pub candidates: Vec<(String, Vec<usize>)>,
+    pub winner_violations: Vec<usize>,
+    // how many times the winner was observed
+    pub frequency: usize,
+}
+
+// a tableau for an underlying form and the winner observed for it; if GEN comes up with the winner
+// more than once (say, with a tone deleted in one and left floating in another), we go with
+// whichever one violates the fewest constraints
+pub fn tableau(
+    underlying: &SyllabifiedCandidate,
+    winner: &str,
+    frequency: usize,
+    constraints: &[Box<dyn Constraint>],
+    gen: &Gen,
+) -> Result<Tableau, LearningError> {
+    let violations = |form: &SyllabifiedCandidate| -> Vec<usize> {
+        constraints
+            .iter()
+            .map(|constraint| constraint.evaluate(form.to_owned()))
+            .collect()
+    };
+
+    let candidates: Vec<(String, Vec<usize>)> = underlying
+        .permute(gen)
+        .iter()
+        .map(|form| (String::from(form.to_owned()), violations(form)))
+        .collect();
+
+    let underlying = String::from(underlying.to_owned());
+
+    let winner_violations = candidates
+        .iter()
+        .filter(|(form, _)| *form == winner)
+        .map(|(_, violations)| violations)
+        .min_by_key(|violations| violations.iter().sum::<usize>())
+        .ok_or(LearningError::WinnerNotGenerated {
+            underlying: underlying.to_owned(),
+            winner: winner.to_owned(),
+        })?
+        .to_owned();
+
+    Ok(Tableau {
+        underlying,
+        winner: winner.to_owned(),
+        candidates,
+        winner_violations,
+        frequency,
+    })
+}
+
+pub fn tableaux(
+    data: &[Datum],
+    constraints: ConstraintSet,
+    gen: &Gen,
+) -> Result<Vec<Tableau>, LearningError> {
+    data.iter()
+        .map(|datum| {
+            tableau(
+                &datum.underlying,
+                &datum.surface,
+                datum.frequency,
+                &constraints(&datum.underlying),
+                gen,
+            )
+        })
+        .collect()
+}
+
+// a tableau for the learners' tests, straight from each candidate's violations, with the winner
+// observed once
+#[cfg(test)]
+pub fn test_tableau(underlying: &str, winner: &str, candidates: &[(&str, Vec<usize>)]) -> Tableau {
+    let candidates: Vec<(String, Vec<usize>)> = candidates
+        .iter()
+        .map(|(form, violations)| (form.to_string(), violations.to_owned()))
+        .collect();
+
+    let winner_violations = candidates
+        .iter()
+        .find(|(form, _)| form == winner)
+        .map(|(_, violations)| violations.to_owned())
+        .expect("The winner has to be one of the candidates!");
+
+    Tableau {
+        underlying: underlying.to_owned(),
+        winner: winner.to_owned(),
+        candidates,
+        winner_violations,
+        frequency: 1,
+    }
+}
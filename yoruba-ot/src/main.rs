@@ -1,10 +1,50 @@
+mod arguments;
+mod cli;
 mod constraint;
+mod corpus;
+mod dot;
+mod erc;
+mod features;
+mod gen;
+mod gla;
+mod harmonic;
+mod inventory;
+mod latex;
+mod learn;
+mod maxent;
+mod rcd;
+mod sgd;
+mod stochastic;
+mod tableau;
+mod tone;
+mod typology;
 mod utils;
 
+use crate::arguments::ranking_arguments;
+use crate::cli::{Options, SubstitutionOptions};
 use crate::constraint::{
-    Constraint, Dep, Ident, Max, MaxFinalV, MaxInitialV, Onset, RankedConstraint, SonSeqPr,
+    Anchor, Constraint, Dep, Ident, Linearity, Max, MaxFinalV, MaxInitialV, MaxTone, NoFloat,
+    OcpTone, Onset, RankedConstraint, SonSeqPr, Syllabify, UnweightedMax, UnweightedOnset,
 };
+use crate::corpus::{read_corpus, read_underlying};
+use crate::dot::dot;
+use crate::erc::ComparativeTableau;
+use crate::gen::Gen;
+use crate::gla::gla;
+use crate::harmonic::{evaluate_hg, WeightedConstraint};
+use crate::inventory::{inventory, load_inventory};
+use crate::latex::latex;
+use crate::learn::{constraint_names, tableau, Learner};
+use crate::maxent::{evaluate_maxent, surface_distribution};
+use crate::rcd::{bcd, rank, rcd, Hierarchy};
+use crate::sgd::sgd;
+use crate::stochastic::{evaluate_stochastic, StochasticConstraint};
+use crate::tableau::ViolationTableau;
+use crate::tone::{join_tones, permute_tones, split_tones, Tone, ToneLevel};
+use crate::typology::factorial_typology;
 use itertools::Itertools;
+use std::collections::HashSet;
+use std::str::FromStr;
 use unicode_segmentation::UnicodeSegmentation;
 
 use utils::{permute_delete, VecRet};
@@ -12,17 +52,13 @@ use utils::{permute_delete, VecRet};
 // string -> syllabified candidate -> random deletions (all winners generated via deletions) ->
 // eval against constraints
 
-// remove accent marks before processing bc we don't have the accented variants of the vowels here?
-// are there other limitations that prevent us from keeping accents?
-//
-// also, i think we look at the first element of the grapheme, right? which should be the bare
-// vowel
+// tone marks are split off of vowels while parsing and kept on their own tier (see tone.rs), so
+// segments only ever hold bare vowels
 
-const VOWELS: [&str; 7] = ["o", "ɛ", "ɔ", "i", "u", "a", "e"];
-
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct SyllabifiedCandidate {
     form: Vec<Segment>,
+    tones: Vec<Tone>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -31,6 +67,8 @@ struct Segment {
     syllable_index: SyllableIndex,
     seg_type: SegmentType,
     morpheme_index: UnderlyingIndex,
+    // position of the underlying segment this one corresponds to; none if epenthetic
+    correspondent: Option<usize>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -52,109 +90,231 @@ enum UnderlyingIndex {
     Initial,
     Middle,
     Final,
+    None,
 }
 
 impl SyllabifiedCandidate {
-    fn permute(&self) -> Vec<Self> {
+    // the segment of this (underlying) form that a surface segment corresponds to, if any
+    fn correspondent(&self, seg: &Segment) -> Option<&Segment> {
+        seg.correspondent.and_then(|position| {
+            self.form
+                .iter()
+                .find(|underlying| underlying.correspondent == Some(position))
+        })
+    }
+
+    // the levels of the tones associated to a segment of this form
+    fn tones_of(&self, seg: &Segment) -> Vec<ToneLevel> {
+        self.tones
+            .iter()
+            .filter(|tone| tone.anchor.is_some() && tone.anchor == seg.correspondent)
+            .map(|tone| tone.level)
+            .collect()
+    }
+
+    // the form with its morpheme boundaries written back in, e.g. "ra-ɔgɛdɛ"; only an underlying
+    // form is sure to still have a segment at the end of every morpheme
+    fn morphemes(&self) -> String {
+        let segments: Vec<String> = self
+            .form
+            .iter()
+            .map(|seg| join_tones(&seg.char, &self.tones_of(seg)))
+            .collect();
+
+        self.form
+            .iter()
+            .zip(segments)
+            .enumerate()
+            .map(|(index, (seg, joined))| {
+                if seg.morpheme_index == UnderlyingIndex::Final && index + 1 < self.form.len() {
+                    format!("{}-", joined)
+                } else {
+                    joined
+                }
+            })
+            .collect()
+    }
+
+    fn permute(&self, gen: &Gen) -> Vec<Self> {
         permute_delete(&self.form)
             .iter()
-            .map(|form| SyllabifiedCandidate {
-                form: syllabify(form.to_owned()),
+            .flat_map(|form| gen.apply(form))
+            .flat_map(|form| {
+                permute_tones(&self.tones, &form, gen.floating_tones)
+                    .into_iter()
+                    .map(move |tones| SyllabifiedCandidate {
+                        form: syllabify(form.to_owned()),
+                        tones,
+                    })
             })
             .collect()
     }
 }
 
 fn get_seg_type(grapheme: &str) -> SegmentType {
-    if VOWELS.contains(&grapheme) {
+    if inventory()
+        .get(grapheme)
+        .is_some_and(|seg| seg.features.syllabic)
+    {
         SegmentType::Vowel
     } else {
         SegmentType::Consonant
     }
 }
 
-impl From<&str> for SyllabifiedCandidate {
-    fn from(str: &str) -> SyllabifiedCandidate {
-        let mut underlying_final: Vec<usize> = str
-            .grapheme_indices(true)
-            .filter_map(|(index, grapheme)| {
-                if grapheme == "-" {
-                    Some(index - 1)
-                } else {
-                    None
-                }
-            })
-            .collect();
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ParseError {
+    // a segment that isn't in the inventory
+    UnknownSegment { segment: String, position: usize },
+    // a morpheme with nothing in it, as in "owo--ki" or an empty word
+    EmptyMorpheme { position: usize },
+    // a morpheme boundary at the very start or end of a word
+    StrayBoundary { position: usize },
+}
 
-        if str.graphemes(true).count() != 0 {
-            underlying_final.push(str.graphemes(true).count() - 1);
+// positions are counted in characters (graphemes, really) from the start of the word
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ParseError::UnknownSegment { segment, position } => {
+                write!(f, "unknown segment {} at position {}", segment, position)
+            }
+            ParseError::EmptyMorpheme { position } => {
+                write!(f, "empty morpheme at position {}", position)
+            }
+            ParseError::StrayBoundary { position } => {
+                write!(
+                    f,
+                    "morpheme boundary at the edge of the word at position {}",
+                    position
+                )
+            }
         }
+    }
+}
 
-        let mut underlying_initial: Vec<usize> = str
-            .grapheme_indices(true)
-            .filter_map(|(index, grapheme)| {
-                if grapheme == "-" {
-                    Some(index + 1)
-                } else {
-                    None
+impl std::error::Error for ParseError {}
+
+// checks that every segment is in the inventory and every boundary has a morpheme on both sides
+fn check_tokens(tokens: &[String]) -> Result<(), ParseError> {
+    // a word needs at least the one morpheme
+    if tokens.is_empty() {
+        return Err(ParseError::EmptyMorpheme { position: 0 });
+    }
+
+    tokens
+        .iter()
+        .enumerate()
+        .try_fold(0, |position, (index, token)| {
+            if token == "-" {
+                if index == 0 || index == tokens.len() - 1 {
+                    return Err(ParseError::StrayBoundary { position });
                 }
-            })
-            .collect();
 
-        underlying_initial.push(0);
-
-        let mut graphemes: Vec<Segment> = str
-            .grapheme_indices(true)
-            .filter_map(|(index, grapheme)| match grapheme {
-                "-" => None,
-                _ => Some(Segment {
-                    char: grapheme.to_owned(),
-                    syllable_index: SyllableIndex::None,
-                    seg_type: get_seg_type(grapheme),
-                    morpheme_index: if underlying_final.contains(&index) {
-                        UnderlyingIndex::Final
-                    } else if underlying_initial.contains(&index) {
-                        UnderlyingIndex::Initial
-                    } else {
-                        UnderlyingIndex::Middle
-                    },
-                }),
+                if tokens[index - 1] == "-" {
+                    return Err(ParseError::EmptyMorpheme { position });
+                }
+            } else if inventory().get(&split_tones(token).0).is_none() {
+                return Err(ParseError::UnknownSegment {
+                    segment: token.to_owned(),
+                    position,
+                });
+            }
+
+            Ok(position + token.graphemes(true).count())
+        })
+        .map(|_| ())
+}
+
+impl TryFrom<&str> for SyllabifiedCandidate {
+    type Error = ParseError;
+
+    fn try_from(str: &str) -> Result<SyllabifiedCandidate, ParseError> {
+        str.parse()
+    }
+}
+
+impl FromStr for SyllabifiedCandidate {
+    type Err = ParseError;
+
+    fn from_str(str: &str) -> Result<SyllabifiedCandidate, ParseError> {
+        let tokens = inventory().tokenize(str);
+
+        check_tokens(&tokens)?;
+
+        let mut segments: Vec<Segment> = tokens
+            .iter()
+            .enumerate()
+            .filter(|(_, token)| *token != "-")
+            .map(|(index, token)| Segment {
+                char: token.to_owned(),
+                syllable_index: SyllableIndex::None,
+                seg_type: get_seg_type(token),
+                morpheme_index: if tokens.get(index + 1).is_none_or(|next| next == "-") {
+                    UnderlyingIndex::Final
+                } else if index == 0 || tokens[index - 1] == "-" {
+                    UnderlyingIndex::Initial
+                } else {
+                    UnderlyingIndex::Middle
+                },
+                correspondent: None,
             })
             .collect();
 
-        // grouping affricates together as non-separate segments
-        // can we do this more cleanly?
-        if !graphemes.is_empty() {
-            for idx in 0..graphemes.len() - 1 {
-                if (graphemes[idx].char == "d" && graphemes[idx + 1].char == "ʒ")
-                    || (graphemes[idx].char == "g" && graphemes[idx + 1].char == "b")
-                {
-                    let char_to_concat = graphemes[idx + 1].char.clone();
-                    graphemes[idx].char.push_str(&char_to_concat);
-                    graphemes.remove(idx + 1);
-                }
-            }
-        }
+        segments
+            .iter_mut()
+            .enumerate()
+            .for_each(|(position, seg)| seg.correspondent = Some(position));
 
-        SyllabifiedCandidate {
-            form: syllabify(graphemes),
-        }
+        // moving tone marks onto their own tier
+        let mut tones: Vec<Tone> = Vec::new();
+
+        segments.iter_mut().for_each(|seg| {
+            let (base, levels) = split_tones(&seg.char);
+
+            seg.char = base;
+            seg.seg_type = get_seg_type(&seg.char);
+
+            tones.extend(levels.into_iter().map(|level| Tone {
+                level,
+                anchor: seg.correspondent,
+                correspondent: None,
+            }));
+        });
+
+        tones
+            .iter_mut()
+            .enumerate()
+            .for_each(|(position, tone)| tone.correspondent = Some(position));
+
+        Ok(SyllabifiedCandidate {
+            form: syllabify(segments),
+            tones,
+        })
     }
 }
 
 impl From<SyllabifiedCandidate> for String {
     fn from(cand: SyllabifiedCandidate) -> String {
-        cand.form.iter().map(|seg| seg.char.to_owned()).collect()
+        cand.form
+            .iter()
+            .map(|seg| join_tones(&seg.char, &cand.tones_of(seg)))
+            .collect()
     }
 }
 
 impl Segment {
+    fn sonority(&self) -> usize {
+        inventory().get(&self.char).map_or(0, |seg| seg.sonority)
+    }
+
     fn morph_type(&self, seg_type: SyllableIndex) -> Segment {
         Segment {
             char: self.char.clone(),
             syllable_index: seg_type,
             seg_type: self.seg_type.clone(),
             morpheme_index: self.morpheme_index.clone(),
+            correspondent: self.correspondent,
         }
     }
 }
@@ -168,7 +328,7 @@ fn mark_vowels(candidate: Vec<Segment>) -> Vec<Segment> {
     candidate
         .iter()
         .map(|segment| {
-            if VOWELS.contains(&segment.char.as_str()) {
+            if segment.seg_type == SegmentType::Vowel {
                 segment.morph_type(SyllableIndex::Nucleus)
             } else {
                 segment.clone()
@@ -228,188 +388,1202 @@ fn syllabify(candidate: Vec<Segment>) -> Vec<Segment> {
                 syllable_index: SyllableIndex::None,
                 seg_type: seg.seg_type.clone(),
                 morpheme_index: seg.morpheme_index.clone(),
+                correspondent: seg.correspondent,
             })
             .collect(),
     )))
 }
 
+// how the constraints in a stratum (constraints sharing a rank) are weighed against each other
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+enum EvalMode {
+    // violations are summed across the stratum
+    #[default]
+    Cumulative,
+    // every constraint is its own stratum, in the order given, so that each one strictly dominates
+    // the ones after it
+    Strict,
+    // the constraints in a stratum are crucially unranked: a candidate wins if it's optimal under
+    // some total ordering of each stratum
+    Refinements,
+}
+
+impl EvalMode {
+    fn evaluate(
+        &self,
+        underlying_candidate: SyllabifiedCandidate,
+        constraints: Vec<RankedConstraint>,
+        gen: &Gen,
+    ) -> Vec<SyllabifiedCandidate> {
+        match self {
+            EvalMode::Cumulative => evaluate(underlying_candidate, constraints, gen),
+            EvalMode::Strict => evaluate_strict(underlying_candidate, constraints, gen),
+            EvalMode::Refinements => evaluate_refinements(underlying_candidate, constraints, gen),
+        }
+    }
+}
+
+fn strata(constraints: &mut [RankedConstraint]) -> Vec<Vec<&RankedConstraint>> {
+    // a stable sort, so constraints keep the order they were given in within a stratum
+    constraints.sort_by_key(|constraint| constraint.rank);
+
+    constraints
+        .iter()
+        .group_by(|constraint| constraint.rank)
+        .into_iter()
+        .map(|(_, group)| group.collect())
+        .collect::<Vec<Vec<&RankedConstraint>>>()
+}
+
+// the forms that do best on a constraint (or a stratum of them)
+fn optimal(
+    forms: Vec<SyllabifiedCandidate>,
+    constraint: &impl Constraint,
+) -> Vec<SyllabifiedCandidate> {
+    match forms.len() {
+        0 => panic!("No forms to evaluate!"),
+        1 => forms,
+        _ => {
+            let rankings: Vec<(SyllabifiedCandidate, usize)> = forms
+                .iter()
+                .map(|form| (form.to_owned(), constraint.evaluate(form.to_owned())))
+                .collect();
+
+            let min: usize = rankings
+                .iter()
+                .min_by(|(_, vio1), (_, vio2)| vio1.cmp(vio2))
+                .expect("Iterator was empty!")
+                .1;
+
+            let next: Vec<SyllabifiedCandidate> = rankings
+                .iter()
+                .filter(|(_, vio_count)| vio_count == &min)
+                .map(|(cand, _)| cand.to_owned())
+                .collect();
+
+            if next.is_empty() {
+                forms
+            } else {
+                next
+            }
+        }
+    }
+}
+
 fn evaluate(
     underlying_candidate: SyllabifiedCandidate,
     mut constraints: Vec<RankedConstraint>,
+    gen: &Gen,
 ) -> Vec<SyllabifiedCandidate> {
-    let surface_forms: Vec<SyllabifiedCandidate> = underlying_candidate.permute();
+    let surface_forms: Vec<SyllabifiedCandidate> = underlying_candidate.permute(gen);
 
-    constraints.sort_by(|const1, const2| const1.rank.cmp(&const2.rank));
+    strata(&mut constraints).iter().fold(surface_forms, optimal)
+}
 
-    let grouped_constraints: Vec<Vec<&RankedConstraint>> = constraints
-        .iter()
-        .group_by(|constraint| constraint.rank)
+fn evaluate_strict(
+    underlying_candidate: SyllabifiedCandidate,
+    mut constraints: Vec<RankedConstraint>,
+    gen: &Gen,
+) -> Vec<SyllabifiedCandidate> {
+    let surface_forms: Vec<SyllabifiedCandidate> = underlying_candidate.permute(gen);
+
+    strata(&mut constraints)
         .into_iter()
-        .map(|(_, group)| group.collect())
-        .collect::<Vec<Vec<&RankedConstraint>>>();
+        .flatten()
+        .fold(surface_forms, optimal)
+}
 
-    grouped_constraints
-        .iter()
-        .fold(surface_forms, |forms, constraint| match forms.len() {
-            0 => panic!("No forms to evaluate!"),
-            1 => forms,
-            _ => {
-                let rankings: Vec<(SyllabifiedCandidate, usize)> = forms
-                    .iter()
-                    .map(|form| (form.to_owned(), constraint.evaluate(form.to_owned())))
-                    .collect();
+fn evaluate_refinements(
+    underlying_candidate: SyllabifiedCandidate,
+    mut constraints: Vec<RankedConstraint>,
+    gen: &Gen,
+) -> Vec<SyllabifiedCandidate> {
+    let surface_forms: Vec<SyllabifiedCandidate> = underlying_candidate.permute(gen);
 
-                #[cfg(debug_assertions)]
-                rankings
-                    .iter()
-                    .map(|(cand, vios)| {
-                        println!(
-                            "{:?} has {:?} violations of {:#?}",
-                            String::from(cand.to_owned()),
-                            vios,
-                            constraint
-                        )
-                    })
-                    // dropping a bunch of references; we just need this to consume the iterator,
-                    // since they're lazy
-                    .for_each(drop);
+    let winners = refinement_winners(surface_forms.clone(), &strata(&mut constraints));
 
-                let min: usize = rankings
-                    .iter()
-                    .min_by(|(_, vio1), (_, vio2)| vio1.cmp(vio2))
-                    .expect("Iterator was empty!")
-                    .1;
+    // keeping the order GEN produced the forms in
+    surface_forms
+        .into_iter()
+        .filter(|form| winners.contains(form))
+        .collect()
+}
 
-                let next: Vec<SyllabifiedCandidate> = rankings
-                    .iter()
-                    .filter(|(_, vio_count)| vio_count == &min)
-                    .map(|(cand, _)| cand.to_owned())
-                    .collect();
+// every form that's optimal under some refinement of the strata; the constraints of a stratum get
+// put in order one at a time, each choice of the next one leaving its own set of survivors, and
+// since different orders keep ending up in the same place, each (stratum, constraints left to
+// place, survivors) state only gets gone through once
+fn refinement_winners(
+    forms: Vec<SyllabifiedCandidate>,
+    strata: &[Vec<&RankedConstraint>],
+) -> Vec<SyllabifiedCandidate> {
+    if strata.is_empty() {
+        return forms;
+    }
 
-                if next.is_empty() {
-                    forms
-                } else {
-                    next
-                }
-            }
+    // every constraint gets evaluated against every form just the once, and from here on both are
+    // referred to by index
+    let constraints: Vec<&RankedConstraint> = strata.iter().flatten().copied().collect();
+    let violations: Vec<Vec<usize>> = forms
+        .iter()
+        .map(|form| {
+            constraints
+                .iter()
+                .map(|constraint| constraint.evaluate(form.to_owned()))
+                .collect()
         })
-}
+        .collect();
+    let strata: Vec<Vec<usize>> = strata
+        .iter()
+        .scan(0, |start, stratum| {
+            *start += stratum.len();
+            Some((*start - stratum.len()..*start).collect())
+        })
+        .collect();
 
-fn main() {
-    use std::io::{stdin, stdout, Write};
+    let mut seen: HashSet<RefinementState> = HashSet::new();
+    let mut winners: Vec<usize> = Vec::new();
 
-    let mut buffer = String::new();
+    refine(
+        (0, strata[0].clone(), (0..forms.len()).collect()),
+        &strata,
+        &violations,
+        &mut seen,
+        &mut winners,
+    );
 
-    loop {
-        print!("> ");
+    winners
+        .into_iter()
+        .map(|form| forms[form].to_owned())
+        .collect()
+}
 
-        stdout().flush().unwrap();
+// which stratum is being put in order, which of its constraints are left to place, and which forms
+// are still in the running
+type RefinementState = (usize, Vec<usize>, Vec<usize>);
+
+fn refine(
+    state: RefinementState,
+    strata: &[Vec<usize>],
+    violations: &[Vec<usize>],
+    seen: &mut HashSet<RefinementState>,
+    winners: &mut Vec<usize>,
+) {
+    if !seen.insert(state.clone()) {
+        return;
+    }
 
-        stdin().read_line(&mut buffer).unwrap();
+    let (stratum, left, survivors) = state;
 
-        let cand: SyllabifiedCandidate = buffer.trim_end().into();
+    if left.is_empty() {
+        match strata.get(stratum + 1) {
+            Some(next) => refine(
+                (stratum + 1, next.to_owned(), survivors),
+                strata,
+                violations,
+                seen,
+                winners,
+            ),
+            None => survivors.into_iter().for_each(|form| {
+                if !winners.contains(&form) {
+                    winners.push(form);
+                }
+            }),
+        }
 
-        println!(
-            "{:?}",
-            evaluate(
-                cand.clone(),
-                vec![
-                    RankedConstraint {
-                        rank: 1,
-                        constraint: Box::new(Ident(cand.clone())) as Box<dyn Constraint>,
-                    },
-                    RankedConstraint {
-                        rank: 1,
-                        constraint: Box::new(Dep(cand.clone())) as Box<dyn Constraint>,
-                    },
-                    RankedConstraint {
-                        rank: 2,
-                        constraint: Box::new(Onset) as Box<dyn Constraint>,
-                    },
-                    RankedConstraint {
-                        rank: 2,
-                        constraint: Box::new(SonSeqPr) as Box<dyn Constraint>,
-                    },
-                    RankedConstraint {
-                        rank: 2,
-                        constraint: Box::new(Max(cand.clone())) as Box<dyn Constraint>,
-                    },
-                    RankedConstraint {
-                        rank: 3,
-                        constraint: Box::new(MaxInitialV(cand.clone())) as Box<dyn Constraint>,
-                    },
-                    RankedConstraint {
-                        rank: 4,
-                        constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
-                    },
-                ],
-            )
+        return;
+    }
+
+    left.iter().for_each(|constraint| {
+        let min = survivors
             .iter()
-            .map(|cand| String::from(cand.to_owned()))
-            .collect::<Vec<String>>()
-        );
+            .map(|form| violations[*form][*constraint])
+            .min()
+            .expect("There's always a survivor!");
+
+        refine(
+            (
+                stratum,
+                left.iter()
+                    .filter(|other| *other != constraint)
+                    .copied()
+                    .collect(),
+                survivors
+                    .iter()
+                    .filter(|form| violations[**form][*constraint] == min)
+                    .copied()
+                    .collect(),
+            ),
+            strata,
+            violations,
+            seen,
+            winners,
+        )
+    });
+}
 
-        buffer.clear();
-    }
+// the ranking we've settled on for Yoruba
+fn yoruba_ranking(cand: &SyllabifiedCandidate) -> Vec<RankedConstraint> {
+    vec![
+        RankedConstraint {
+            rank: 1,
+            constraint: Box::new(Ident(cand.clone())) as Box<dyn Constraint>,
+        },
+        RankedConstraint {
+            rank: 1,
+            constraint: Box::new(Dep(cand.clone())) as Box<dyn Constraint>,
+        },
+        RankedConstraint {
+            rank: 1,
+            constraint: Box::new(Linearity(cand.clone())) as Box<dyn Constraint>,
+        },
+        RankedConstraint {
+            rank: 2,
+            constraint: Box::new(Onset) as Box<dyn Constraint>,
+        },
+        RankedConstraint {
+            rank: 2,
+            constraint: Box::new(SonSeqPr) as Box<dyn Constraint>,
+        },
+        RankedConstraint {
+            rank: 2,
+            constraint: Box::new(Max(cand.clone())) as Box<dyn Constraint>,
+        },
+        RankedConstraint {
+            rank: 3,
+            constraint: Box::new(MaxInitialV(cand.clone())) as Box<dyn Constraint>,
+        },
+        RankedConstraint {
+            rank: 4,
+            constraint: Box::new(MaxFinalV(cand.clone())) as Box<dyn Constraint>,
+        },
+        // tone only breaks ties left over by the segmental analysis
+        RankedConstraint {
+            rank: 5,
+            constraint: Box::new(MaxTone(cand.clone())) as Box<dyn Constraint>,
+        },
+        RankedConstraint {
+            rank: 5,
+            constraint: Box::new(NoFloat) as Box<dyn Constraint>,
+        },
+        RankedConstraint {
+            rank: 6,
+            constraint: Box::new(OcpTone) as Box<dyn Constraint>,
+        },
+    ]
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use crate::constraint::Syllabify;
+// the same analysis as a weighted grammar: the summing up that Onset and Max get by counting
+// three times over is done with weights here, and every stratum outweighs everything below it, so
+// this should pick the same winners as yoruba_ranking
+fn yoruba_weights(cand: &SyllabifiedCandidate) -> Vec<WeightedConstraint> {
+    vec![
+        WeightedConstraint {
+            weight: 100.0,
+            constraint: Box::new(Ident(cand.clone())),
+        },
+        WeightedConstraint {
+            weight: 100.0,
+            constraint: Box::new(Dep(cand.clone())),
+        },
+        WeightedConstraint {
+            weight: 100.0,
+            constraint: Box::new(Linearity(cand.clone())),
+        },
+        WeightedConstraint {
+            weight: 3.0,
+            constraint: Box::new(UnweightedOnset),
+        },
+        WeightedConstraint {
+            weight: 1.0,
+            constraint: Box::new(SonSeqPr),
+        },
+        WeightedConstraint {
+            weight: 3.0,
+            constraint: Box::new(UnweightedMax(cand.clone())),
+        },
+        WeightedConstraint {
+            weight: 1.0,
+            constraint: Box::new(Anchor(cand.clone())),
+        },
+        WeightedConstraint {
+            weight: 0.1,
+            constraint: Box::new(MaxInitialV(cand.clone())),
+        },
+        WeightedConstraint {
+            weight: 0.01,
+            constraint: Box::new(MaxFinalV(cand.clone())),
+        },
+        WeightedConstraint {
+            weight: 0.001,
+            constraint: Box::new(MaxTone(cand.clone())),
+        },
+        WeightedConstraint {
+            weight: 0.001,
+            constraint: Box::new(NoFloat),
+        },
+        WeightedConstraint {
+            weight: 0.0001,
+            constraint: Box::new(OcpTone),
+        },
+    ]
+}
 
-    #[test]
-    fn test_ident_1() {
-        let cand1: SyllabifiedCandidate = "owókíowó".into();
-        let cand2: SyllabifiedCandidate = "ówakíówó".into();
+// the ranking again, spread out on a continuous scale for Stochastic OT; MaxInitialV and MaxFinalV
+// are close enough that the noise swaps them every so often, which is where we'd expect variable
+// elision (ragɛ̀dɛ̀ next to rɔ̀gɛ̀dɛ̀) to come from; with strict domination, Onset has to sit above
+// Max and SonSeqPr below it, or we get either no elision or far too much
+fn yoruba_ranking_values(cand: &SyllabifiedCandidate) -> Vec<StochasticConstraint> {
+    vec![
+        StochasticConstraint {
+            ranking_value: 100.0,
+            constraint: Box::new(Ident(cand.clone())),
+        },
+        StochasticConstraint {
+            ranking_value: 100.0,
+            constraint: Box::new(Dep(cand.clone())),
+        },
+        StochasticConstraint {
+            ranking_value: 100.0,
+            constraint: Box::new(Linearity(cand.clone())),
+        },
+        StochasticConstraint {
+            ranking_value: 100.0,
+            constraint: Box::new(Anchor(cand.clone())),
+        },
+        StochasticConstraint {
+            ranking_value: 90.0,
+            constraint: Box::new(Onset),
+        },
+        StochasticConstraint {
+            ranking_value: 80.0,
+            constraint: Box::new(UnweightedMax(cand.clone())),
+        },
+        StochasticConstraint {
+            ranking_value: 70.0,
+            constraint: Box::new(SonSeqPr),
+        },
+        StochasticConstraint {
+            ranking_value: 66.0,
+            constraint: Box::new(MaxInitialV(cand.clone())),
+        },
+        StochasticConstraint {
+            ranking_value: 60.0,
+            constraint: Box::new(MaxFinalV(cand.clone())),
+        },
+        StochasticConstraint {
+            ranking_value: 50.0,
+            constraint: Box::new(MaxTone(cand.clone())),
+        },
+        StochasticConstraint {
+            ranking_value: 50.0,
+            constraint: Box::new(NoFloat),
+        },
+        StochasticConstraint {
+            ranking_value: 40.0,
+            constraint: Box::new(OcpTone),
+        },
+    ]
+}
 
-        let ident = Ident(cand1);
+// the constraints the learners rank; Onset and Max are the unweighted versions, since the
+// learners do the job the weights baked into the others do
+fn yoruba_constraints(cand: &SyllabifiedCandidate) -> Vec<Box<dyn Constraint>> {
+    vec![
+        Box::new(Ident(cand.clone())),
+        Box::new(Dep(cand.clone())),
+        Box::new(Linearity(cand.clone())),
+        Box::new(Anchor(cand.clone())),
+        Box::new(UnweightedOnset),
+        Box::new(UnweightedMax(cand.clone())),
+        Box::new(SonSeqPr),
+        Box::new(Syllabify),
+        Box::new(MaxInitialV(cand.clone())),
+        Box::new(MaxFinalV(cand.clone())),
+        Box::new(MaxTone(cand.clone())),
+        Box::new(NoFloat),
+        Box::new(OcpTone),
+    ]
+}
 
-        assert_eq!(ident.evaluate(cand2), 3);
-    }
+fn main() {
+    use std::io::{stdin, stdout, Write};
 
-    #[test]
-    fn test_dep_1() {
-        let syllabified_candidate: SyllabifiedCandidate = dbg!("owoktwiowo".into());
-        assert_eq!(Dep(syllabified_candidate.clone()).evaluate("".into()), 0)
+    let exit = |err: String| -> ! {
+        eprintln!("{}", err);
+        std::process::exit(1)
+    };
+
+    let Options {
+        mut gen,
+        substitution,
+        inventory,
+        mode,
+        harmonic,
+        maxent,
+        stochastic,
+        trials,
+        learn,
+        arguments,
+        dot: dot_path,
+        typology,
+        typology_method,
+        latex: latex_path,
+        tipa,
+        learner,
+        gla: gla_settings,
+        sgd: sgd_settings,
+    } = Options::from_args(std::env::args().skip(1)).unwrap_or_else(|err| exit(err));
+
+    if let Some(path) = inventory {
+        load_inventory(&path).unwrap_or_else(|err| exit(err));
     }
 
-    #[test]
-    fn test_ssp_1() {
-        let syllabified_candidate: SyllabifiedCandidate = "owókíowó".into();
-        assert_eq!(SonSeqPr.evaluate(syllabified_candidate), 7);
-    }
+    gen.substitution = substitution.map(SubstitutionOptions::substitution);
 
-    #[test]
-    fn test_ssp_2() {
-        let syllabified_candidate: SyllabifiedCandidate = "".into();
-        assert_eq!(SonSeqPr.evaluate(syllabified_candidate), 0);
-    }
+    // learning a grammar to use instead of ours: a hierarchy from RCD or BCD, ranking values from
+    // the GLA, or weights from MaxEnt
+    let mut hierarchy: Option<Hierarchy> = None;
+    let mut ranking_values: Option<Vec<f64>> = None;
+    let mut weights: Option<Vec<f64>> = None;
+    // the crucial dominations the data make, if we've worked them out
+    let mut crucial: Option<Vec<(usize, usize)>> = None;
 
-    #[test]
-    fn test_onset_1() {
-        let syllabified_candidate: SyllabifiedCandidate = dbg!("owoktwiowo".into());
-        assert_eq!(Onset.evaluate(syllabified_candidate), 6);
-    }
+    let names = constraint_names(yoruba_constraints);
 
-    #[test]
-    fn test_syllabify_constraint() {
-        let syllabified_candidate: SyllabifiedCandidate = dbg!("owoktwiowo".into());
-        assert_eq!(Syllabify.evaluate(syllabified_candidate), 1);
-    }
+    if let Some(path) = learn {
+        let data = read_corpus(&path).unwrap_or_else(|err| exit(err));
 
-    #[test]
-    fn test_evaluate_1() {
-        let cand: SyllabifiedCandidate = "owo-ki-owo".into();
-        let surface_forms = evaluate(
-            cand.clone(),
-            vec![
-                RankedConstraint {
-                    rank: 1,
-                    constraint: Box::new(Ident(cand.clone())) as Box<dyn Constraint>,
-                },
-                RankedConstraint {
-                    rank: 1,
+        if arguments {
+            let arguments = ranking_arguments(&data, yoruba_constraints, &gen)
+                .unwrap_or_else(|err| exit(err.to_string()));
+
+            // each ERC that's needed, along with the winner and loser it comes from
+            arguments.ercs.iter().for_each(|erc| {
+                println!("{} ({} ~ {})", erc.display(&names), erc.winner, erc.loser)
+            });
+
+            // and the Hasse diagram of the rankings that have to hold
+            arguments
+                .hasse
+                .iter()
+                .for_each(|(higher, lower)| println!("{} >> {}", names[*higher], names[*lower]));
+
+            crucial = Some(arguments.hasse);
+        }
+
+        match learner {
+            Learner::Rcd | Learner::Bcd => {
+                let learned = if learner == Learner::Bcd {
+                    bcd(&data, yoruba_constraints, &gen)
+                } else {
+                    rcd(&data, yoruba_constraints, &gen)
+                }
+                .unwrap_or_else(|err| exit(err.to_string()));
+
+                println!("{}", learned.display(&names));
+
+                hierarchy = Some(learned);
+            }
+            Learner::Gla => {
+                let learned = gla(
+                    &data,
+                    yoruba_constraints,
+                    &gen,
+                    &gla_settings,
+                    &mut rand::thread_rng(),
+                )
+                .unwrap_or_else(|err| exit(err.to_string()));
+
+                learned
+                    .ranking_values
+                    .iter()
+                    .zip(names.iter())
+                    .sorted_by(|(v1, _), (v2, _)| v2.total_cmp(v1))
+                    .for_each(|(ranking_value, name)| println!("{} {:.3}", name, ranking_value));
+
+                // the share of the data the learner got wrong, epoch by epoch
+                learned
+                    .learning_curve
+                    .iter()
+                    .enumerate()
+                    .for_each(|(epoch, errors)| println!("epoch {} {:.4}", epoch + 1, errors));
+
+                ranking_values = Some(learned.ranking_values);
+            }
+            Learner::MaxEnt => {
+                let learned = sgd(
+                    &data,
+                    yoruba_constraints,
+                    &gen,
+                    &sgd_settings,
+                    &mut rand::thread_rng(),
+                )
+                .unwrap_or_else(|err| exit(err.to_string()));
+
+                learned
+                    .weights
+                    .iter()
+                    .zip(names.iter())
+                    .sorted_by(|(w1, _), (w2, _)| w2.total_cmp(w1))
+                    .for_each(|(weight, name)| println!("{} {:.3}", name, weight));
+
+                println!("log-likelihood {:.4}", learned.log_likelihood);
+
+                // what share of each underlying form's outputs came out as each surface form, and
+                // what share the grammar predicts
+                learned.fit.iter().for_each(|fit| {
+                    println!(
+                        "{} {} {:.4} {:.4}",
+                        fit.underlying, fit.surface, fit.observed, fit.predicted
+                    )
+                });
+
+                weights = Some(learned.weights);
+            }
+        }
+    }
+
+    // every language our constraints predict for some underlying forms, along with the
+    // stratified hierarchy RCD finds for it, and how many total rankings come up with it if we've
+    // gone through them
+    if let Some(path) = typology {
+        let underlying = read_underlying(&path).unwrap_or_else(|err| exit(err));
+
+        factorial_typology(&underlying, yoruba_constraints, &gen, typology_method)
+            .iter()
+            .enumerate()
+            .for_each(|(index, language)| {
+                let hierarchy = rank(&language.ercs, &names)
+                    .expect("Every language in the typology has some ranking!");
+
+                match &language.rankings {
+                    Some(rankings) => println!(
+                        "language {} ({}; {} rankings)",
+                        index + 1,
+                        hierarchy.display(&names),
+                        rankings.len()
+                    ),
+                    None => println!("language {} ({})", index + 1, hierarchy.display(&names)),
+                }
+
+                language.outputs.iter().for_each(|(underlying, winners)| {
+                    println!("    {} -> {}", underlying, winners.join(", "))
+                });
+            });
+    }
+
+    // drawing whichever ranking we ended up with: the one the data argue for if we've got it,
+    // then whatever was learned, then ours
+    if let Some(path) = dot_path {
+        let (names, edges) = match (crucial, &hierarchy, &ranking_values, &weights) {
+            (Some(edges), _, _, _) => (names, edges),
+            (None, Some(hierarchy), _, _) => (names, hierarchy.edges()),
+            (None, None, Some(ranking_values), _) => (
+                names,
+                Hierarchy::from_ranking_values(ranking_values).edges(),
+            ),
+            (None, None, None, Some(_)) => exit("MaxEnt weights don't rank anything".to_owned()),
+            (None, None, None, None) => {
+                let ranking = yoruba_ranking(&SyllabifiedCandidate {
+                    form: vec![],
+                    tones: vec![],
+                });
+
+                (
+                    ranking.iter().map(|constraint| constraint.name()).collect(),
+                    Hierarchy::from_ranks(
+                        &ranking
+                            .iter()
+                            .map(|constraint| constraint.rank)
+                            .collect::<Vec<usize>>(),
+                    )
+                    .edges(),
+                )
+            }
+        };
+
+        std::fs::write(&path, dot(&names, &edges))
+            .unwrap_or_else(|err| exit(format!("{}: {}", path, err)));
+    }
+
+    // the ranking forms get evaluated with: whatever was learned, or ours
+    let ranking = |cand: &SyllabifiedCandidate| match &hierarchy {
+        Some(hierarchy) => hierarchy.ranked(yoruba_constraints(cand)),
+        None => yoruba_ranking(cand),
+    };
+
+    // a LaTeX tableau for each of a list of underlying forms, for the paper
+    if let Some(path) = latex_path {
+        if mode == EvalMode::Refinements {
+            exit("Refinements don't come down to a single tableau".to_owned());
+        }
+
+        read_underlying(&path)
+            .unwrap_or_else(|err| exit(err))
+            .iter()
+            .for_each(|underlying| {
+                let tableau = ViolationTableau::new(
+                    underlying,
+                    ranking(underlying),
+                    &gen,
+                    mode == EvalMode::Strict,
+                );
+
+                println!("{}", latex(&tableau, tipa));
+            });
+
+        // the tableaux are all there is to print, so there's nothing to go on to
+        return;
+    }
+
+    let mut buffer = String::new();
+
+    loop {
+        print!("> ");
+
+        stdout().flush().unwrap();
+
+        buffer.clear();
+
+        // end of input
+        if stdin().read_line(&mut buffer).unwrap() == 0 {
+            println!();
+            break;
+        }
+
+        let mut words = buffer.split_whitespace();
+
+        let cand: SyllabifiedCandidate = match words.next().unwrap_or("").parse() {
+            Ok(cand) => cand,
+            Err(err) => {
+                println!("{}", err);
+                continue;
+            }
+        };
+
+        // an underlying form followed by a winner gets a comparative tableau of the winner against
+        // everything else GEN comes up with, instead of being evaluated
+        if let Some(winner) = words.next() {
+            let comparative = winner
+                .parse::<SyllabifiedCandidate>()
+                .map_err(|err| err.to_string())
+                .and_then(|winner| {
+                    tableau(
+                        &cand,
+                        &String::from(winner),
+                        1,
+                        &yoruba_constraints(&cand),
+                        &gen,
+                    )
+                    .map_err(|err| err.to_string())
+                })
+                .map(|tableau| {
+                    ComparativeTableau::new(&tableau, &constraint_names(yoruba_constraints))
+                });
+
+            match comparative {
+                Ok(comparative) => print!("{}", comparative),
+                Err(err) => println!("{}", err),
+            }
+
+            continue;
+        }
+
+        // learned ranking values only make sense evaluated stochastically
+        if stochastic.is_some() || ranking_values.is_some() {
+            let constraints = match &ranking_values {
+                Some(ranking_values) => yoruba_constraints(&cand)
+                    .into_iter()
+                    .zip(ranking_values)
+                    .map(|(constraint, ranking_value)| StochasticConstraint {
+                        ranking_value: *ranking_value,
+                        constraint,
+                    })
+                    .collect(),
+                None => yoruba_ranking_values(&cand),
+            };
+
+            evaluate_stochastic(
+                cand.clone(),
+                &constraints,
+                &gen,
+                gla_settings.noise,
+                trials,
+                &mut rand::thread_rng(),
+            )
+            .iter()
+            .for_each(|(form, frequency)| println!("{} {:.4}", form, frequency));
+
+            continue;
+        }
+
+        let weighted = || match &weights {
+            Some(weights) => yoruba_constraints(&cand)
+                .into_iter()
+                .zip(weights)
+                .map(|(constraint, weight)| WeightedConstraint {
+                    weight: *weight,
+                    constraint,
+                })
+                .collect(),
+            None => yoruba_weights(&cand),
+        };
+
+        // learned weights are evaluated with MaxEnt unless we ask for Harmonic Grammar
+        if maxent || (weights.is_some() && !harmonic) {
+            surface_distribution(evaluate_maxent(cand.clone(), &weighted(), &gen))
+                .iter()
+                .for_each(|(form, probability)| println!("{} {:.4}", form, probability));
+
+            continue;
+        }
+
+        if harmonic {
+            println!(
+                "{:?}",
+                evaluate_hg(cand.clone(), weighted(), &gen)
+                    .into_iter()
+                    .map(String::from)
+                    .collect::<Vec<String>>()
+            );
+
+            continue;
+        }
+
+        let ranking = ranking(&cand);
+
+        // refinements don't come down to one decision for each candidate, so there's no tableau
+        // to draw for them, just their winners
+        match mode {
+            EvalMode::Refinements => println!(
+                "{:?}",
+                mode.evaluate(cand.clone(), ranking, &gen)
+                    .into_iter()
+                    .map(String::from)
+                    .collect::<Vec<String>>()
+            ),
+            _ => print!(
+                "{}",
+                ViolationTableau::new(&cand, ranking, &gen, mode == EvalMode::Strict)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::corpus::Datum;
+    use crate::erc::{set_entails, Erc, Preference};
+    use crate::gen::{Epenthesis, Metathesis, Substitution};
+    use crate::gla::GlaSettings;
+    use crate::learn::LearningError;
+    use crate::sgd::SgdSettings;
+    use crate::typology::{Language, Method};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    // every underlying form from the paper along with the surface form it comes out as
+    const ELISION_DATA: [(&str, &str); 17] = [
+        ("owo-ki-owo", "owokowo"),
+        ("ɔmɔ-ki-ɔmɔ", "ɔmɔkɔmɔ"),
+        ("se-olu", "solu"),
+        ("dʒɛ-ede", "dʒede"),
+        ("dʒo-ɛwu", "dʒɛwu"),
+        ("ra-ɔgɛdɛ", "ragɛdɛ"),
+        ("ni-oko", "noko"),
+        ("si-ɔd͡ʒa", "sɔd͡ʒa"),
+        ("gbe-inɔ", "gbenɔ"),
+        ("gba-iʃɛ", "gbaʃɛ"),
+        ("wo-ilɛ", "wolɛ"),
+        ("dʒi-aʃɔ", "dʒaʃɔ"),
+        ("dʒu-igi", "dʒugi"),
+        ("lu-ilɛ", "lulɛ"),
+        ("bu-ɔba", "bɔba"),
+        ("ru-epo", "repo"),
+        ("bu-omi", "bomi"),
+    ];
+
+    fn elision_data() -> Vec<Datum> {
+        ELISION_DATA
+            .iter()
+            .map(|(underlying, surface)| Datum {
+                underlying: underlying.parse().unwrap(),
+                surface: surface.to_string(),
+                frequency: 1,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ident_1() {
+        let cand1: SyllabifiedCandidate = "owókíowó".parse().unwrap();
+        let cand2: SyllabifiedCandidate = "ówakíówó".parse().unwrap();
+
+        let ident = Ident(cand1);
+
+        assert_eq!(ident.evaluate(cand2), 3);
+    }
+
+    #[test]
+    fn test_dep_1() {
+        let syllabified_candidate: SyllabifiedCandidate = dbg!("owoktwiowo".parse().unwrap());
+        assert_eq!(
+            Dep(syllabified_candidate.clone()).evaluate(SyllabifiedCandidate::default()),
+            0
+        )
+    }
+
+    #[test]
+    fn test_dep_2() {
+        let cand: SyllabifiedCandidate = "owo-ki-owo".parse().unwrap();
+        let gen = Gen {
+            epenthesis: Some(Epenthesis {
+                segments: vec!["j".to_owned()],
+                max: 1,
+            }),
+            ..Gen::default()
+        };
+
+        let epenthesized = cand
+            .permute(&gen)
+            .into_iter()
+            .find(|surface| String::from(surface.to_owned()) == "owokijowo")
+            .unwrap();
+
+        assert_eq!(Dep(cand.clone()).evaluate(epenthesized.clone()), 1);
+        assert_eq!(Max(cand).evaluate(epenthesized), 0);
+    }
+
+    #[test]
+    fn test_ident_2() {
+        let cand: SyllabifiedCandidate = "owo-ki-owo".parse().unwrap();
+        let gen = Gen {
+            substitution: Some(Substitution::vowels(1)),
+            ..Gen::default()
+        };
+
+        let substituted = cand
+            .permute(&gen)
+            .into_iter()
+            .find(|surface| String::from(surface.to_owned()) == "owokeowo")
+            .unwrap();
+
+        assert_eq!(Ident(cand.clone()).evaluate(substituted.clone()), 1);
+        assert_eq!(Max(cand).evaluate(substituted), 0);
+    }
+
+    #[test]
+    fn test_multigraphs_1() {
+        let cand: SyllabifiedCandidate = "ɛ̀-gbgbɛ".parse().unwrap();
+
+        assert_eq!(
+            cand.form
+                .iter()
+                .map(|seg| (seg.char.as_str(), seg.morpheme_index.clone()))
+                .collect::<Vec<(&str, UnderlyingIndex)>>(),
+            vec![
+                ("ɛ", UnderlyingIndex::Final),
+                ("gb", UnderlyingIndex::Initial),
+                ("gb", UnderlyingIndex::Middle),
+                ("ɛ", UnderlyingIndex::Final),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_error_1() {
+        assert_eq!(
+            "owo-xi".parse::<SyllabifiedCandidate>(),
+            Err(ParseError::UnknownSegment {
+                segment: "x".to_owned(),
+                position: 4,
+            })
+        );
+        assert_eq!(
+            "owo--ki".parse::<SyllabifiedCandidate>(),
+            Err(ParseError::EmptyMorpheme { position: 4 })
+        );
+        assert_eq!(
+            "-owo".parse::<SyllabifiedCandidate>(),
+            Err(ParseError::StrayBoundary { position: 0 })
+        );
+        assert_eq!(
+            SyllabifiedCandidate::try_from("gbɛ̀-"),
+            Err(ParseError::StrayBoundary { position: 3 })
+        );
+    }
+
+    #[test]
+    fn test_parse_error_2() {
+        // tones and tie bars don't count against a segment
+        assert!("ɔ̀d͡ʒá".parse::<SyllabifiedCandidate>().is_ok());
+        assert_eq!(
+            "".parse::<SyllabifiedCandidate>().map(|cand| cand.form),
+            Err(ParseError::EmptyMorpheme { position: 0 })
+        );
+    }
+
+    #[test]
+    fn test_correspondence_1() {
+        let cand: SyllabifiedCandidate = "owo-ki-owo".parse().unwrap();
+        let gen = Gen {
+            substitution: Some(Substitution::vowels(1)),
+            ..Gen::default()
+        };
+
+        // i -> o, which a grapheme diff would read as a deletion plus an insertion
+        let assimilated = cand
+            .permute(&gen)
+            .into_iter()
+            .find(|surface| {
+                String::from(surface.to_owned()) == "owokoowo" && surface.form.len() == 8
+            })
+            .unwrap();
+
+        assert_eq!(Ident(cand.clone()).evaluate(assimilated.clone()), 1);
+        assert_eq!(Dep(cand.clone()).evaluate(assimilated.clone()), 0);
+        assert_eq!(Max(cand).evaluate(assimilated), 0);
+    }
+
+    #[test]
+    fn test_correspondence_2() {
+        let cand: SyllabifiedCandidate = "ra-ɔgɛdɛ".parse().unwrap();
+        let gen = Gen {
+            epenthesis: Some(Epenthesis {
+                segments: vec!["j".to_owned()],
+                max: 1,
+            }),
+            substitution: Some(Substitution::vowels(1)),
+            ..Gen::default()
+        };
+
+        // a deleted, ɔ -> a, j epenthesized before it
+        let surface = cand
+            .permute(&gen)
+            .into_iter()
+            .find(|surface| {
+                String::from(surface.to_owned()) == "rjagɛdɛ"
+                    && surface.form[2].correspondent == Some(2)
+            })
+            .unwrap();
+
+        assert_eq!(Ident(cand.clone()).evaluate(surface.clone()), 1);
+        assert_eq!(Dep(cand.clone()).evaluate(surface.clone()), 1);
+        assert_eq!(Max(cand.clone()).evaluate(surface.clone()), 3);
+        assert_eq!(MaxFinalV(cand.clone()).evaluate(surface.clone()), 1);
+        assert_eq!(MaxInitialV(cand).evaluate(surface), 0);
+    }
+
+    #[test]
+    fn test_tone_tier_1() {
+        let cand: SyllabifiedCandidate = "ra-ɔ̀gɛ̀dɛ̀".parse().unwrap();
+
+        assert_eq!(
+            cand.form
+                .iter()
+                .map(|seg| seg.char.to_owned())
+                .collect::<String>(),
+            "raɔgɛdɛ"
+        );
+        assert_eq!(
+            cand.tones,
+            vec![
+                Tone {
+                    level: ToneLevel::Low,
+                    anchor: Some(2),
+                    correspondent: Some(0),
+                },
+                Tone {
+                    level: ToneLevel::Low,
+                    anchor: Some(4),
+                    correspondent: Some(1),
+                },
+                Tone {
+                    level: ToneLevel::Low,
+                    anchor: Some(6),
+                    correspondent: Some(2),
+                },
+            ]
+        );
+        assert_eq!(String::from(cand), "raɔ̀gɛ̀dɛ̀");
+    }
+
+    #[test]
+    fn test_tone_tier_2() {
+        let cand: SyllabifiedCandidate = "ra-ɔ̀gɛ̀dɛ̀".parse().unwrap();
+        let gen = Gen {
+            floating_tones: true,
+            ..Gen::default()
+        };
+
+        // ɔ deleted; its tone can go down with it, float, or dock onto a or ɛ
+        let surface_forms: Vec<SyllabifiedCandidate> = cand
+            .permute(&gen)
+            .into_iter()
+            .filter(|surface| {
+                surface.form.len() == 6 && surface.form.iter().all(|seg| seg.char != "ɔ")
+            })
+            .collect();
+
+        assert_eq!(
+            surface_forms
+                .iter()
+                .map(|surface| String::from(surface.to_owned()))
+                .collect::<Vec<String>>(),
+            vec!["ragɛ̀dɛ̀", "ragɛ̀dɛ̀", "ràgɛ̀dɛ̀", "ragɛ̀̀dɛ̀"]
+        );
+        assert!(surface_forms[1]
+            .tones
+            .iter()
+            .any(|tone| tone.anchor.is_none()));
+    }
+
+    #[test]
+    fn test_tone_tier_3() {
+        let cand: SyllabifiedCandidate = "ra-ɔ̀gɛ̀dɛ̀".parse().unwrap();
+
+        // without reassociation, a tone just goes down with its vowel
+        assert!(cand
+            .permute(&Gen::default())
+            .iter()
+            .filter(|surface| surface.form.iter().all(|seg| seg.char != "ɔ"))
+            .all(
+                |surface| surface.tones.iter().all(|tone| tone.anchor != Some(2))
+                    && surface.tones.iter().all(|tone| tone.anchor.is_some())
+            ));
+    }
+
+    #[test]
+    fn test_max_tone_1() {
+        let cand: SyllabifiedCandidate = "ra-ɔ̀gɛ̀dɛ̀".parse().unwrap();
+        let gen = Gen {
+            floating_tones: true,
+            ..Gen::default()
+        };
+
+        let surface_forms: Vec<(usize, usize)> = cand
+            .permute(&gen)
+            .into_iter()
+            .filter(|surface| {
+                surface.form.len() == 6 && surface.form.iter().all(|seg| seg.char != "ɔ")
+            })
+            .map(|surface| {
+                (
+                    MaxTone(cand.clone()).evaluate(surface.clone()),
+                    NoFloat.evaluate(surface),
+                )
+            })
+            .collect();
+
+        // deleted, floating, docked left, docked right
+        assert_eq!(surface_forms, vec![(1, 0), (0, 1), (0, 0), (0, 0)]);
+    }
+
+    #[test]
+    fn test_ocp_tone_1() {
+        assert_eq!(OcpTone.evaluate("ɔ̀gɛ̀dɛ̀".parse().unwrap()), 2);
+        assert_eq!(OcpTone.evaluate("owókíowó".parse().unwrap()), 2);
+        assert_eq!(OcpTone.evaluate("ɔ̀gɛ́dɛ̀".parse().unwrap()), 0);
+    }
+
+    #[test]
+    fn test_linearity_1() {
+        let cand: SyllabifiedCandidate = "owo-ki-owo".parse().unwrap();
+        let gen = Gen {
+            metathesis: Some(Metathesis { max: 2 }),
+            ..Gen::default()
+        };
+
+        let metathesized = cand
+            .permute(&gen)
+            .into_iter()
+            .filter(|surface| String::from(surface.to_owned()) == "wookiowo")
+            .collect::<Vec<SyllabifiedCandidate>>();
+
+        // only one way to get there: swapping the first two segments
+        assert_eq!(metathesized.len(), 1);
+        assert_eq!(Linearity(cand.clone()).evaluate(metathesized[0].clone()), 1);
+        assert_eq!(Linearity(cand.clone()).evaluate(cand), 0);
+    }
+
+    #[test]
+    fn test_linearity_2() {
+        let cand: SyllabifiedCandidate = "abde".parse().unwrap();
+
+        let mut reversed = cand.clone();
+        reversed.form.reverse();
+
+        // every one of the six pairs is out of order
+        assert_eq!(Linearity(cand).evaluate(reversed), 6);
+    }
+
+    #[test]
+    fn test_linearity_3() {
+        let cand: SyllabifiedCandidate = "aba".parse().unwrap();
+
+        let mut deleted = cand.clone();
+        deleted.form.remove(0);
+
+        // deleting the first a doesn't put anything out of order, even though the a that's left
+        // could be taken for the first one
+        assert_eq!(Linearity(cand).evaluate(deleted), 0);
+    }
+
+    #[test]
+    fn test_moved_edges_1() {
+        let cand: SyllabifiedCandidate = "owo-ki-owo".parse().unwrap();
+        let nothing = SyllabifiedCandidate::default();
+
+        // deleting the whole word doesn't leave its edges where they were
+        assert_eq!(Anchor(cand.clone()).evaluate(nothing.clone()), 1);
+        assert_eq!(Max(cand).evaluate(nothing), 8 * 3 + 1);
+    }
+
+    #[test]
+    fn test_ssp_1() {
+        let syllabified_candidate: SyllabifiedCandidate = "owókíowó".parse().unwrap();
+        assert_eq!(SonSeqPr.evaluate(syllabified_candidate), 7);
+    }
+
+    #[test]
+    fn test_ssp_2() {
+        let syllabified_candidate = SyllabifiedCandidate::default();
+        assert_eq!(SonSeqPr.evaluate(syllabified_candidate), 0);
+    }
+
+    #[test]
+    fn test_onset_1() {
+        let syllabified_candidate: SyllabifiedCandidate = dbg!("owoktwiowo".parse().unwrap());
+        assert_eq!(Onset.evaluate(syllabified_candidate), 6);
+    }
+
+    #[test]
+    fn test_onset_2() {
+        // toned vowels are still vowels
+        let syllabified_candidate: SyllabifiedCandidate = "òó".parse().unwrap();
+        assert_eq!(Onset.evaluate(syllabified_candidate), 6);
+    }
+
+    #[test]
+    fn test_syllabify_constraint() {
+        let syllabified_candidate: SyllabifiedCandidate = dbg!("owoktwiowo".parse().unwrap());
+        assert_eq!(Syllabify.evaluate(syllabified_candidate), 1);
+    }
+
+    #[test]
+    fn test_evaluate_1() {
+        let cand: SyllabifiedCandidate = "owo-ki-owo".parse().unwrap();
+        let surface_forms = evaluate(
+            cand.clone(),
+            vec![
+                RankedConstraint {
+                    rank: 1,
+                    constraint: Box::new(Ident(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 1,
                     constraint: Box::new(Dep(cand.clone())) as Box<dyn Constraint>,
                 },
                 RankedConstraint {
@@ -433,6 +1607,7 @@ mod test {
                     constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
                 },
             ],
+            &Gen::default(),
         )
         .iter()
         .map(|cand| String::from(cand.to_owned()))
@@ -443,7 +1618,7 @@ mod test {
 
     #[test]
     fn test_evaluate_2() {
-        let cand: SyllabifiedCandidate = "ɔmɔ-ki-ɔmɔ".into();
+        let cand: SyllabifiedCandidate = "ɔmɔ-ki-ɔmɔ".parse().unwrap();
         let surface_forms = evaluate(
             cand.clone(),
             vec![
@@ -476,6 +1651,7 @@ mod test {
                     constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
                 },
             ],
+            &Gen::default(),
         )
         .iter()
         .map(|cand| String::from(cand.to_owned()))
@@ -486,7 +1662,7 @@ mod test {
 
     #[test]
     fn test_evaluate_3() {
-        let cand: SyllabifiedCandidate = "se-olu".into();
+        let cand: SyllabifiedCandidate = "se-olu".parse().unwrap();
         let surface_forms = evaluate(
             cand.clone(),
             vec![
@@ -519,6 +1695,7 @@ mod test {
                     constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
                 },
             ],
+            &Gen::default(),
         )
         .iter()
         .map(|cand| String::from(cand.to_owned()))
@@ -529,7 +1706,7 @@ mod test {
 
     #[test]
     fn test_evaluate_4() {
-        let cand: SyllabifiedCandidate = "dʒɛ-ede".into();
+        let cand: SyllabifiedCandidate = "dʒɛ-ede".parse().unwrap();
         let surface_forms = evaluate(
             cand.clone(),
             vec![
@@ -562,6 +1739,7 @@ mod test {
                     constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
                 },
             ],
+            &Gen::default(),
         )
         .iter()
         .map(|cand| String::from(cand.to_owned()))
@@ -572,7 +1750,7 @@ mod test {
 
     #[test]
     fn test_evaluate_5() {
-        let cand: SyllabifiedCandidate = "dʒo-ɛwu".into();
+        let cand: SyllabifiedCandidate = "dʒo-ɛwu".parse().unwrap();
         let surface_forms = evaluate(
             cand.clone(),
             vec![
@@ -605,6 +1783,7 @@ mod test {
                     constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
                 },
             ],
+            &Gen::default(),
         )
         .iter()
         .map(|cand| String::from(cand.to_owned()))
@@ -615,7 +1794,7 @@ mod test {
 
     #[test]
     fn test_evaluate_6() {
-        let cand: SyllabifiedCandidate = "ra-ɔgɛdɛ".into();
+        let cand: SyllabifiedCandidate = "ra-ɔgɛdɛ".parse().unwrap();
         let surface_forms = evaluate(
             cand.clone(),
             vec![
@@ -648,6 +1827,7 @@ mod test {
                     constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
                 },
             ],
+            &Gen::default(),
         )
         .iter()
         .map(|cand| String::from(cand.to_owned()))
@@ -658,7 +1838,7 @@ mod test {
 
     #[test]
     fn test_evaluate_7() {
-        let cand: SyllabifiedCandidate = "ni-oko".into();
+        let cand: SyllabifiedCandidate = "ni-oko".parse().unwrap();
         let surface_forms = evaluate(
             cand.clone(),
             vec![
@@ -691,6 +1871,7 @@ mod test {
                     constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
                 },
             ],
+            &Gen::default(),
         )
         .iter()
         .map(|cand| String::from(cand.to_owned()))
@@ -701,7 +1882,7 @@ mod test {
 
     #[test]
     fn test_evaluate_8() {
-        let cand: SyllabifiedCandidate = "si-ɔd͡ʒa".into();
+        let cand: SyllabifiedCandidate = "si-ɔd͡ʒa".parse().unwrap();
         let surface_forms = evaluate(
             cand.clone(),
             vec![
@@ -734,6 +1915,7 @@ mod test {
                     constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
                 },
             ],
+            &Gen::default(),
         )
         .iter()
         .map(|cand| String::from(cand.to_owned()))
@@ -744,7 +1926,7 @@ mod test {
 
     #[test]
     fn test_evaluate_9() {
-        let cand: SyllabifiedCandidate = "gbe-inɔ".into();
+        let cand: SyllabifiedCandidate = "gbe-inɔ".parse().unwrap();
         let surface_forms = evaluate(
             cand.clone(),
             vec![
@@ -777,6 +1959,7 @@ mod test {
                     constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
                 },
             ],
+            &Gen::default(),
         )
         .iter()
         .map(|cand| String::from(cand.to_owned()))
@@ -787,7 +1970,7 @@ mod test {
 
     #[test]
     fn test_evaluate_10() {
-        let cand: SyllabifiedCandidate = "gba-iʃɛ".into();
+        let cand: SyllabifiedCandidate = "gba-iʃɛ".parse().unwrap();
         let surface_forms = evaluate(
             cand.clone(),
             vec![
@@ -820,6 +2003,7 @@ mod test {
                     constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
                 },
             ],
+            &Gen::default(),
         )
         .iter()
         .map(|cand| String::from(cand.to_owned()))
@@ -830,7 +2014,7 @@ mod test {
 
     #[test]
     fn test_evaluate_11() {
-        let cand: SyllabifiedCandidate = "wo-ilɛ".into();
+        let cand: SyllabifiedCandidate = "wo-ilɛ".parse().unwrap();
         let surface_forms = evaluate(
             cand.clone(),
             vec![
@@ -863,6 +2047,7 @@ mod test {
                     constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
                 },
             ],
+            &Gen::default(),
         )
         .iter()
         .map(|cand| String::from(cand.to_owned()))
@@ -873,7 +2058,7 @@ mod test {
 
     #[test]
     fn test_evaluate_12() {
-        let cand: SyllabifiedCandidate = "dʒi-aʃɔ".into();
+        let cand: SyllabifiedCandidate = "dʒi-aʃɔ".parse().unwrap();
         let surface_forms = evaluate(
             cand.clone(),
             vec![
@@ -906,6 +2091,7 @@ mod test {
                     constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
                 },
             ],
+            &Gen::default(),
         )
         .iter()
         .map(|cand| String::from(cand.to_owned()))
@@ -916,7 +2102,7 @@ mod test {
 
     #[test]
     fn test_evaluate_13() {
-        let cand: SyllabifiedCandidate = "dʒu-igi".into();
+        let cand: SyllabifiedCandidate = "dʒu-igi".parse().unwrap();
         let surface_forms = evaluate(
             cand.clone(),
             vec![
@@ -949,6 +2135,7 @@ mod test {
                     constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
                 },
             ],
+            &Gen::default(),
         )
         .iter()
         .map(|cand| String::from(cand.to_owned()))
@@ -959,7 +2146,7 @@ mod test {
 
     #[test]
     fn test_evaluate_14() {
-        let cand: SyllabifiedCandidate = "lu-ilɛ".into();
+        let cand: SyllabifiedCandidate = "lu-ilɛ".parse().unwrap();
         let surface_forms = evaluate(
             cand.clone(),
             vec![
@@ -992,6 +2179,7 @@ mod test {
                     constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
                 },
             ],
+            &Gen::default(),
         )
         .iter()
         .map(|cand| String::from(cand.to_owned()))
@@ -1002,7 +2190,7 @@ mod test {
 
     #[test]
     fn test_evaluate_15() {
-        let cand: SyllabifiedCandidate = "bu-ɔba".into();
+        let cand: SyllabifiedCandidate = "bu-ɔba".parse().unwrap();
         let surface_forms = evaluate(
             cand.clone(),
             vec![
@@ -1035,6 +2223,7 @@ mod test {
                     constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
                 },
             ],
+            &Gen::default(),
         )
         .iter()
         .map(|cand| String::from(cand.to_owned()))
@@ -1045,7 +2234,7 @@ mod test {
 
     #[test]
     fn test_evaluate_16() {
-        let cand: SyllabifiedCandidate = "ru-epo".into();
+        let cand: SyllabifiedCandidate = "ru-epo".parse().unwrap();
         let surface_forms = evaluate(
             cand.clone(),
             vec![
@@ -1078,6 +2267,7 @@ mod test {
                     constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
                 },
             ],
+            &Gen::default(),
         )
         .iter()
         .map(|cand| String::from(cand.to_owned()))
@@ -1088,7 +2278,7 @@ mod test {
 
     #[test]
     fn test_evaluate_17() {
-        let cand: SyllabifiedCandidate = "bu-omi".into();
+        let cand: SyllabifiedCandidate = "bu-omi".parse().unwrap();
         let surface_forms = evaluate(
             cand.clone(),
             vec![
@@ -1121,6 +2311,7 @@ mod test {
                     constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
                 },
             ],
+            &Gen::default(),
         )
         .iter()
         .map(|cand| String::from(cand.to_owned()))
@@ -1128,4 +2319,1006 @@ mod test {
 
         assert_eq!(surface_forms, vec!["bomi"])
     }
+
+    #[test]
+    fn test_evaluate_epenthesis_1() {
+        let cand: SyllabifiedCandidate = "owo-ki-owo".parse().unwrap();
+        let surface_forms = evaluate(
+            cand.clone(),
+            vec![
+                RankedConstraint {
+                    rank: 1,
+                    constraint: Box::new(Ident(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 1,
+                    constraint: Box::new(Dep(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 2,
+                    constraint: Box::new(Onset) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 2,
+                    constraint: Box::new(SonSeqPr) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 2,
+                    constraint: Box::new(Max(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 3,
+                    constraint: Box::new(MaxInitialV(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 4,
+                    constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
+                },
+            ],
+            &Gen {
+                epenthesis: Some(Epenthesis {
+                    segments: vec!["j".to_owned(), "i".to_owned()],
+                    max: 1,
+                }),
+                ..Gen::default()
+            },
+        )
+        .iter()
+        .map(|cand| String::from(cand.to_owned()))
+        .collect::<Vec<String>>();
+
+        assert_eq!(surface_forms, vec!["owokowo"])
+    }
+
+    #[test]
+    fn test_evaluate_epenthesis_2() {
+        let cand: SyllabifiedCandidate = "owo-ki-owo".parse().unwrap();
+        let surface_forms = evaluate(
+            cand.clone(),
+            vec![
+                RankedConstraint {
+                    rank: 1,
+                    constraint: Box::new(Ident(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 2,
+                    constraint: Box::new(Onset) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 3,
+                    constraint: Box::new(Max(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 4,
+                    constraint: Box::new(Dep(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 5,
+                    constraint: Box::new(SonSeqPr) as Box<dyn Constraint>,
+                },
+            ],
+            &Gen {
+                epenthesis: Some(Epenthesis {
+                    segments: vec!["j".to_owned(), "i".to_owned()],
+                    max: 2,
+                }),
+                ..Gen::default()
+            },
+        )
+        .iter()
+        .map(|cand| String::from(cand.to_owned()))
+        .collect::<Vec<String>>();
+
+        assert_eq!(surface_forms, vec!["jowokijowo"])
+    }
+
+    #[test]
+    fn test_evaluate_substitution_1() {
+        let cand: SyllabifiedCandidate = "ra-ɔgɛdɛ".parse().unwrap();
+        let surface_forms = evaluate(
+            cand.clone(),
+            vec![
+                RankedConstraint {
+                    rank: 1,
+                    constraint: Box::new(Ident(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 1,
+                    constraint: Box::new(Dep(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 2,
+                    constraint: Box::new(Onset) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 2,
+                    constraint: Box::new(SonSeqPr) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 2,
+                    constraint: Box::new(Max(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 3,
+                    constraint: Box::new(MaxInitialV(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 4,
+                    constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
+                },
+            ],
+            &Gen {
+                substitution: Some(Substitution::vowels(1)),
+                ..Gen::default()
+            },
+        )
+        .iter()
+        .map(|cand| String::from(cand.to_owned()))
+        .collect::<Vec<String>>();
+
+        assert_eq!(surface_forms, vec!["ragɛdɛ"])
+    }
+
+    #[test]
+    fn test_evaluate_substitution_2() {
+        let cand: SyllabifiedCandidate = "owo-ki-owo".parse().unwrap();
+        let surface_forms = evaluate(
+            cand.clone(),
+            vec![
+                RankedConstraint {
+                    rank: 1,
+                    constraint: Box::new(Max(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 1,
+                    constraint: Box::new(Dep(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 2,
+                    constraint: Box::new(SonSeqPr) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 3,
+                    constraint: Box::new(Ident(cand)) as Box<dyn Constraint>,
+                },
+            ],
+            &Gen {
+                substitution: Some(Substitution::vowels(1)),
+                ..Gen::default()
+            },
+        )
+        .iter()
+        .map(|cand| String::from(cand.to_owned()))
+        .collect::<Vec<String>>();
+
+        assert_eq!(surface_forms, vec!["owokaowo"])
+    }
+
+    #[test]
+    fn test_evaluate_metathesis_1() {
+        let cand: SyllabifiedCandidate = "ab".parse().unwrap();
+        let surface_forms = evaluate(
+            cand.clone(),
+            vec![
+                RankedConstraint {
+                    rank: 1,
+                    constraint: Box::new(Onset) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 1,
+                    constraint: Box::new(Max(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 2,
+                    constraint: Box::new(Linearity(cand)) as Box<dyn Constraint>,
+                },
+            ],
+            &Gen {
+                metathesis: Some(Metathesis { max: 1 }),
+                ..Gen::default()
+            },
+        )
+        .iter()
+        .map(|cand| String::from(cand.to_owned()))
+        .collect::<Vec<String>>();
+
+        assert_eq!(surface_forms, vec!["ba"])
+    }
+
+    #[test]
+    fn test_evaluate_tone_1() {
+        let cand: SyllabifiedCandidate = "ra-ɔ̀gɛ̀dɛ̀".parse().unwrap();
+        let surface_forms = evaluate(
+            cand.clone(),
+            vec![
+                RankedConstraint {
+                    rank: 1,
+                    constraint: Box::new(Ident(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 1,
+                    constraint: Box::new(Dep(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 1,
+                    constraint: Box::new(MaxTone(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 1,
+                    constraint: Box::new(NoFloat) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 2,
+                    constraint: Box::new(Onset) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 3,
+                    constraint: Box::new(SonSeqPr) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 3,
+                    constraint: Box::new(Max(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 4,
+                    constraint: Box::new(MaxInitialV(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 5,
+                    constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 6,
+                    constraint: Box::new(OcpTone) as Box<dyn Constraint>,
+                },
+            ],
+            &Gen {
+                floating_tones: true,
+                ..Gen::default()
+            },
+        )
+        .iter()
+        .map(|cand| String::from(cand.to_owned()))
+        .collect::<Vec<String>>();
+
+        // the low tone of ɔ is what's preserved, so it's the vowel that stays too
+        assert_eq!(surface_forms, vec!["rɔ̀gɛ̀dɛ̀"])
+    }
+
+    #[test]
+    fn test_evaluate_tone_2() {
+        let cand: SyllabifiedCandidate = "ra-ɔ̀gɛ̀dɛ̀".parse().unwrap();
+        let surface_forms = evaluate(
+            cand.clone(),
+            vec![
+                RankedConstraint {
+                    rank: 1,
+                    constraint: Box::new(Ident(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 1,
+                    constraint: Box::new(Dep(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 2,
+                    constraint: Box::new(Onset) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 3,
+                    constraint: Box::new(SonSeqPr) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 3,
+                    constraint: Box::new(Max(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 4,
+                    constraint: Box::new(MaxInitialV(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 5,
+                    constraint: Box::new(MaxFinalV(cand.clone())) as Box<dyn Constraint>,
+                },
+                RankedConstraint {
+                    rank: 6,
+                    constraint: Box::new(MaxTone(cand.clone())) as Box<dyn Constraint>,
+                },
+            ],
+            &Gen::default(),
+        )
+        .iter()
+        .map(|cand| String::from(cand.to_owned()))
+        .collect::<Vec<String>>();
+
+        // with tone ranked at the bottom, the segmental analysis deletes ɔ, tone and all
+        assert_eq!(surface_forms, vec!["ragɛ̀dɛ̀"])
+    }
+
+    #[test]
+    fn test_evaluate_strict_1() {
+        let cand: SyllabifiedCandidate = "ra-ɔgɛdɛ".parse().unwrap();
+
+        // without the stratum summing them up, Onset on its own gets rid of every onsetless vowel
+        let surface_forms = EvalMode::Strict
+            .evaluate(
+                cand.clone(),
+                vec![
+                    RankedConstraint {
+                        rank: 1,
+                        constraint: Box::new(Ident(cand.clone())) as Box<dyn Constraint>,
+                    },
+                    RankedConstraint {
+                        rank: 1,
+                        constraint: Box::new(Dep(cand.clone())) as Box<dyn Constraint>,
+                    },
+                    RankedConstraint {
+                        rank: 2,
+                        constraint: Box::new(Onset) as Box<dyn Constraint>,
+                    },
+                    RankedConstraint {
+                        rank: 2,
+                        constraint: Box::new(SonSeqPr) as Box<dyn Constraint>,
+                    },
+                    RankedConstraint {
+                        rank: 2,
+                        constraint: Box::new(Max(cand.clone())) as Box<dyn Constraint>,
+                    },
+                    RankedConstraint {
+                        rank: 3,
+                        constraint: Box::new(MaxInitialV(cand.clone())) as Box<dyn Constraint>,
+                    },
+                    RankedConstraint {
+                        rank: 4,
+                        constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
+                    },
+                ],
+                &Gen::default(),
+            )
+            .iter()
+            .map(|cand| String::from(cand.to_owned()))
+            .collect::<Vec<String>>();
+
+        assert_eq!(surface_forms, vec!["ragd"])
+    }
+
+    #[test]
+    fn test_evaluate_strict_2() {
+        let cand: SyllabifiedCandidate = "ra-ɔgɛdɛ".parse().unwrap();
+
+        // and with Max on top, nothing gets deleted at all
+        let surface_forms = EvalMode::Strict
+            .evaluate(
+                cand.clone(),
+                vec![
+                    RankedConstraint {
+                        rank: 1,
+                        constraint: Box::new(Ident(cand.clone())) as Box<dyn Constraint>,
+                    },
+                    RankedConstraint {
+                        rank: 1,
+                        constraint: Box::new(Dep(cand.clone())) as Box<dyn Constraint>,
+                    },
+                    RankedConstraint {
+                        rank: 2,
+                        constraint: Box::new(Max(cand.clone())) as Box<dyn Constraint>,
+                    },
+                    RankedConstraint {
+                        rank: 2,
+                        constraint: Box::new(Onset) as Box<dyn Constraint>,
+                    },
+                    RankedConstraint {
+                        rank: 2,
+                        constraint: Box::new(SonSeqPr) as Box<dyn Constraint>,
+                    },
+                    RankedConstraint {
+                        rank: 3,
+                        constraint: Box::new(MaxInitialV(cand.clone())) as Box<dyn Constraint>,
+                    },
+                    RankedConstraint {
+                        rank: 4,
+                        constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
+                    },
+                ],
+                &Gen::default(),
+            )
+            .iter()
+            .map(|cand| String::from(cand.to_owned()))
+            .collect::<Vec<String>>();
+
+        assert_eq!(surface_forms, vec!["raɔgɛdɛ"])
+    }
+
+    #[test]
+    fn test_evaluate_refinements_1() {
+        let cand: SyllabifiedCandidate = "ra-ɔgɛdɛ".parse().unwrap();
+
+        // each ordering of Onset, SonSeqPr and Max picks its own winner
+        let surface_forms = EvalMode::Refinements
+            .evaluate(
+                cand.clone(),
+                vec![
+                    RankedConstraint {
+                        rank: 1,
+                        constraint: Box::new(Ident(cand.clone())) as Box<dyn Constraint>,
+                    },
+                    RankedConstraint {
+                        rank: 1,
+                        constraint: Box::new(Dep(cand.clone())) as Box<dyn Constraint>,
+                    },
+                    RankedConstraint {
+                        rank: 2,
+                        constraint: Box::new(Onset) as Box<dyn Constraint>,
+                    },
+                    RankedConstraint {
+                        rank: 2,
+                        constraint: Box::new(SonSeqPr) as Box<dyn Constraint>,
+                    },
+                    RankedConstraint {
+                        rank: 2,
+                        constraint: Box::new(Max(cand.clone())) as Box<dyn Constraint>,
+                    },
+                    RankedConstraint {
+                        rank: 3,
+                        constraint: Box::new(MaxInitialV(cand.clone())) as Box<dyn Constraint>,
+                    },
+                    RankedConstraint {
+                        rank: 4,
+                        constraint: Box::new(MaxFinalV(cand)) as Box<dyn Constraint>,
+                    },
+                ],
+                &Gen::default(),
+            )
+            .iter()
+            .map(|cand| String::from(cand.to_owned()))
+            .collect::<Vec<String>>();
+
+        assert_eq!(surface_forms, vec!["raɔgɛdɛ", "ragɛdɛ", "ragd"])
+    }
+
+    #[test]
+    fn test_evaluate_refinements_2() {
+        let cand: SyllabifiedCandidate = "ra-ɔgɛdɛ".parse().unwrap();
+        let constraints: Vec<Box<dyn Constraint>> = vec![
+            Box::new(Ident(cand.clone())),
+            Box::new(Dep(cand.clone())),
+            Box::new(Linearity(cand.clone())),
+            Box::new(Onset),
+            Box::new(SonSeqPr),
+            Box::new(Max(cand.clone())),
+            Box::new(MaxInitialV(cand.clone())),
+            Box::new(MaxFinalV(cand.clone())),
+            Box::new(MaxTone(cand.clone())),
+            Box::new(NoFloat),
+            Box::new(OcpTone),
+        ];
+
+        // with everything in one stratum there are 11! orderings, but far fewer places they can
+        // get to
+        let surface_forms = EvalMode::Refinements
+            .evaluate(
+                cand,
+                constraints
+                    .into_iter()
+                    .map(|constraint| RankedConstraint {
+                        rank: 1,
+                        constraint,
+                    })
+                    .collect(),
+                &Gen::default(),
+            )
+            .iter()
+            .map(|cand| String::from(cand.to_owned()))
+            .collect::<Vec<String>>();
+
+        assert_eq!(
+            surface_forms,
+            vec![
+                "raɔgɛdɛ",
+                "raɔgdɛ",
+                "raɔgd",
+                "ragɛdɛ",
+                "ragdɛ",
+                "ragd",
+                "rɔgɛdɛ",
+                "rɔgdɛ",
+                "rɔgd"
+            ]
+        )
+    }
+
+    #[test]
+    fn test_yoruba_weights_1() {
+        // the weighted version of the analysis agrees with the ranked one on everything
+        ELISION_DATA.iter().for_each(|(underlying, surface)| {
+            let cand: SyllabifiedCandidate = underlying.parse().unwrap();
+
+            let ranked = evaluate(cand.clone(), yoruba_ranking(&cand), &Gen::default());
+            let weighted = evaluate_hg(cand.clone(), yoruba_weights(&cand), &Gen::default());
+
+            assert_eq!(ranked, weighted);
+            assert_eq!(
+                weighted
+                    .into_iter()
+                    .map(String::from)
+                    .collect::<Vec<String>>(),
+                vec![*surface]
+            );
+        });
+    }
+
+    #[test]
+    fn test_yoruba_weights_2() {
+        // the one-source exception rɔ̀gɛ̀dɛ̀ comes out as a less likely variant of ragɛ̀dɛ̀
+        let cand: SyllabifiedCandidate = "ra-ɔ̀gɛ̀dɛ̀".parse().unwrap();
+        let gen = Gen {
+            floating_tones: true,
+            ..Gen::default()
+        };
+
+        let distribution =
+            surface_distribution(evaluate_maxent(cand.clone(), &yoruba_weights(&cand), &gen));
+
+        let probability = |surface: &str| {
+            distribution
+                .iter()
+                .find(|(form, _)| form == surface)
+                .unwrap()
+                .1
+        };
+
+        assert_eq!(distribution[0].0, "ragɛ̀dɛ̀");
+        assert!(probability("ragɛ̀dɛ̀") > 0.4);
+        assert!(probability("rɔ̀gɛ̀dɛ̀") > 0.05);
+        assert!((distribution.iter().map(|(_, p)| p).sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_yoruba_ranking_values_1() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        ELISION_DATA.iter().for_each(|(underlying, surface)| {
+            let cand: SyllabifiedCandidate = underlying.parse().unwrap();
+
+            let frequencies = evaluate_stochastic(
+                cand.clone(),
+                &yoruba_ranking_values(&cand),
+                &Gen::default(),
+                2.0,
+                1000,
+                &mut rng,
+            );
+
+            assert_eq!(frequencies[0].0, *surface);
+            assert!(frequencies[0].1 > 0.9);
+        });
+    }
+
+    #[test]
+    fn test_yoruba_ranking_values_2() {
+        // rɔgɛdɛ shows up as a variant every so often
+        let cand: SyllabifiedCandidate = "ra-ɔgɛdɛ".parse().unwrap();
+
+        let frequencies = evaluate_stochastic(
+            cand.clone(),
+            &yoruba_ranking_values(&cand),
+            &Gen::default(),
+            2.0,
+            1000,
+            &mut StdRng::seed_from_u64(0),
+        );
+
+        assert_eq!(frequencies[1].0, "rɔgɛdɛ");
+        assert!(frequencies[1].1 > 0.02 && frequencies[1].1 < 0.2);
+    }
+
+    #[test]
+    fn test_anchor_1() {
+        let cand: SyllabifiedCandidate = "owo-ki-owo".parse().unwrap();
+
+        assert_eq!(Anchor(cand.clone()).evaluate(cand), 0);
+    }
+
+    #[test]
+    fn test_rcd_1() {
+        let data = elision_data();
+
+        let hierarchy = rcd(&data, yoruba_constraints, &Gen::default()).unwrap();
+
+        assert_eq!(
+            hierarchy.display(&constraint_names(yoruba_constraints)),
+            "Ident, Dep, Linearity, Anchor, Syllabify, MaxTone, *Float, OCP-Tone >> Onset >> Max \
+                >> SonSeqPr >> MaxInitialV >> MaxFinalV"
+        );
+
+        // and the ranking it learns gets all of the data right
+        data.iter().for_each(|datum| {
+            assert_eq!(
+                evaluate(
+                    datum.underlying.clone(),
+                    hierarchy.ranked(yoruba_constraints(&datum.underlying)),
+                    &Gen::default(),
+                )
+                .into_iter()
+                .map(String::from)
+                .collect::<Vec<String>>(),
+                vec![datum.surface.to_owned()]
+            )
+        });
+    }
+
+    #[test]
+    fn test_bcd_1() {
+        let data = elision_data();
+
+        let hierarchy = bcd(&data, yoruba_constraints, &Gen::default()).unwrap();
+
+        // markedness as high as it'll go, and Ident, Dep and Linearity, which nothing in the data
+        // needs, all the way down
+        assert_eq!(
+            hierarchy.display(&constraint_names(yoruba_constraints)),
+            "Syllabify, *Float, OCP-Tone >> Anchor >> Onset >> Max >> SonSeqPr \
+                >> MaxInitialV >> Ident, Dep, Linearity, MaxFinalV, MaxTone"
+        );
+
+        data.iter().for_each(|datum| {
+            assert_eq!(
+                evaluate(
+                    datum.underlying.clone(),
+                    hierarchy.ranked(yoruba_constraints(&datum.underlying)),
+                    &Gen::default(),
+                )
+                .into_iter()
+                .map(String::from)
+                .collect::<Vec<String>>(),
+                vec![datum.surface.to_owned()]
+            )
+        });
+    }
+
+    #[test]
+    fn test_rcd_2() {
+        let data: Vec<Datum> = ["ragɛdɛ", "rɔgɛdɛ"]
+            .iter()
+            .map(|surface| Datum {
+                underlying: "ra-ɔgɛdɛ".parse().unwrap(),
+                surface: surface.to_string(),
+                frequency: 1,
+            })
+            .collect();
+
+        assert!(matches!(
+            rcd(&data, yoruba_constraints, &Gen::default()),
+            Err(LearningError::Inconsistent { .. })
+        ));
+
+        assert_eq!(
+            rcd(&data[..1], yoruba_constraints, &Gen::default()).map(|_| ()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_rcd_3() {
+        let data = vec![Datum {
+            underlying: "ra-ɔgɛdɛ".parse().unwrap(),
+            surface: "jagɛdɛ".to_owned(),
+            frequency: 1,
+        }];
+
+        assert_eq!(
+            rcd(&data, yoruba_constraints, &Gen::default()),
+            Err(LearningError::WinnerNotGenerated {
+                underlying: "raɔgɛdɛ".to_owned(),
+                winner: "jagɛdɛ".to_owned(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_name_1() {
+        let cand: SyllabifiedCandidate = "ra-o".parse().unwrap();
+
+        // the tripled constraints don't pass for the plain ones
+        assert_eq!(Onset.name(), "Onset*3");
+        assert_eq!(Max(cand.clone()).name(), "Max*3");
+        assert_eq!(UnweightedOnset.name(), "Onset");
+        assert_eq!(UnweightedMax(cand).name(), "Max");
+        assert_eq!(NoFloat.name(), "*Float");
+    }
+
+    #[test]
+    fn test_gla_1() {
+        let data = elision_data();
+
+        let settings = GlaSettings::default();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let learned = gla(
+            &data,
+            yoruba_constraints,
+            &Gen::default(),
+            &settings,
+            &mut rng,
+        )
+        .unwrap();
+
+        // it gets better as it goes
+        let curve = &learned.learning_curve;
+        assert!(curve[curve.len() - 10..].iter().sum::<f64>() < curve[..10].iter().sum::<f64>());
+
+        // and the values it learns get the observed form out most of the time for every datum
+        data.iter().for_each(|datum| {
+            let constraints: Vec<StochasticConstraint> = yoruba_constraints(&datum.underlying)
+                .into_iter()
+                .zip(&learned.ranking_values)
+                .map(|(constraint, ranking_value)| StochasticConstraint {
+                    ranking_value: *ranking_value,
+                    constraint,
+                })
+                .collect();
+
+            assert_eq!(
+                evaluate_stochastic(
+                    datum.underlying.clone(),
+                    &constraints,
+                    &Gen::default(),
+                    settings.noise,
+                    100,
+                    &mut rng,
+                )[0]
+                .0,
+                datum.surface
+            );
+        });
+    }
+
+    #[test]
+    fn test_sgd_1() {
+        let mut data = elision_data();
+
+        // rɔ̀gɛ̀dɛ̀ shows up next to ragɛ̀dɛ̀ every so often
+        data.push(Datum {
+            underlying: "ra-ɔgɛdɛ".parse().unwrap(),
+            surface: "rɔgɛdɛ".to_owned(),
+            frequency: 1,
+        });
+        data[5].frequency = 3;
+
+        let learned = sgd(
+            &data,
+            yoruba_constraints,
+            &Gen::default(),
+            &SgdSettings::default(),
+            &mut StdRng::seed_from_u64(0),
+        )
+        .unwrap();
+
+        assert!(learned.weights.iter().all(|weight| *weight >= 0.0));
+        assert!(learned.log_likelihood < 0.0);
+
+        // every observed form gets a probability, and the ones that never vary come out most of
+        // the time
+        assert_eq!(learned.fit.len(), 18);
+        learned
+            .fit
+            .iter()
+            .filter(|fit| fit.observed == 1.0)
+            .for_each(|fit| assert!(fit.predicted > 0.5, "{:?}", fit));
+
+        // and the variable ones get about what they were observed at
+        let variable = learned
+            .fit
+            .iter()
+            .find(|fit| fit.surface == "ragɛdɛ")
+            .unwrap();
+        assert_eq!(variable.observed, 0.75);
+        assert!((variable.predicted - 0.75).abs() < 0.15, "{:?}", variable);
+    }
+
+    #[test]
+    fn test_comparative_tableau_1() {
+        let cand: SyllabifiedCandidate = "ra-ɔgɛdɛ".parse().unwrap();
+        let comparative = ComparativeTableau::new(
+            &tableau(
+                &cand,
+                "ragɛdɛ",
+                1,
+                &yoruba_constraints(&cand),
+                &Gen::default(),
+            )
+            .unwrap(),
+            &constraint_names(yoruba_constraints),
+        );
+
+        let faithful = comparative
+            .ercs
+            .iter()
+            .find(|erc| erc.loser == "raɔgɛdɛ")
+            .unwrap();
+        let preference = |name: &str| {
+            faithful.preferences[comparative.names.iter().position(|n| n == name).unwrap()]
+        };
+
+        // deleting the ɔ gets rid of an onsetless syllable at the cost of a vowel
+        assert_eq!(preference("Onset"), Preference::W);
+        assert_eq!(preference("Max"), Preference::L);
+        assert_eq!(preference("Ident"), Preference::E);
+
+        // and no loser comes out the same as the winner
+        assert!(comparative.ercs.iter().all(|erc| erc.loser != "ragɛdɛ"));
+    }
+
+    #[test]
+    fn test_ranking_arguments_1() {
+        let names = constraint_names(yoruba_constraints);
+        let arguments =
+            ranking_arguments(&elision_data(), yoruba_constraints, &Gen::default()).unwrap();
+
+        // the rankings the paper argues for, one after the other
+        assert_eq!(
+            arguments
+                .hasse
+                .iter()
+                .map(|(higher, lower)| format!("{} >> {}", names[*higher], names[*lower]))
+                .collect::<Vec<String>>(),
+            vec![
+                "Anchor >> Onset",
+                "Onset >> Max",
+                "Max >> SonSeqPr",
+                "SonSeqPr >> MaxInitialV",
+                "MaxInitialV >> MaxFinalV",
+            ]
+        );
+
+        // which takes a handful of ERCs, each of them needed
+        assert!(!arguments.ercs.is_empty());
+        arguments.ercs.iter().enumerate().for_each(|(index, erc)| {
+            let others: Vec<Erc> = arguments
+                .ercs
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != index)
+                .map(|(_, other)| other.to_owned())
+                .collect();
+
+            assert!(!set_entails(&others, erc));
+        });
+    }
+
+    #[test]
+    fn test_yoruba_typology_1() {
+        let data = elision_data();
+        let underlying: Vec<SyllabifiedCandidate> =
+            data.iter().map(|datum| datum.underlying.clone()).collect();
+
+        let languages = factorial_typology(
+            &underlying,
+            yoruba_constraints,
+            &Gen::default(),
+            Method::BruteForce,
+        );
+
+        let yoruba = |languages: &[Language]| -> Language {
+            languages
+                .iter()
+                .find(|language| {
+                    language
+                        .outputs
+                        .iter()
+                        .zip(&data)
+                        .all(|((_, winners), datum)| *winners == vec![datum.surface.to_owned()])
+                })
+                .unwrap()
+                .to_owned()
+        };
+
+        // Yoruba is one of the languages, and every ranking that comes up with it has Onset over
+        // Max
+        let names = constraint_names(yoruba_constraints);
+        let rankings = yoruba(&languages).rankings.unwrap();
+        assert!(rankings.iter().all(|ranking| {
+            ranking
+                .iter()
+                .position(|constraint| names[*constraint] == "Onset")
+                < ranking
+                    .iter()
+                    .position(|constraint| names[*constraint] == "Max")
+        }));
+
+        // no ranking is in more than one language
+        let rankings: Vec<&Vec<Vec<usize>>> = languages
+            .iter()
+            .map(|language| language.rankings.as_ref().unwrap())
+            .collect();
+        assert_eq!(
+            rankings
+                .iter()
+                .map(|rankings| rankings.len())
+                .sum::<usize>(),
+            (1..=rankings[0][0].len()).product::<usize>()
+        );
+
+        // and going by ERCs gets the same languages, without going through any rankings
+        let by_ercs = factorial_typology(
+            &underlying,
+            yoruba_constraints,
+            &Gen::default(),
+            Method::Ercs,
+        );
+        let outputs = |languages: &[Language]| -> Vec<Vec<(String, Vec<String>)>> {
+            languages
+                .iter()
+                .map(|language| language.outputs.to_owned())
+                .sorted()
+                .collect()
+        };
+        assert_eq!(outputs(&by_ercs), outputs(&languages));
+        assert_eq!(
+            rank(&yoruba(&by_ercs).ercs, &names)
+                .unwrap()
+                .display(&names),
+            "Ident, Dep, Linearity, Anchor, Syllabify, MaxTone, *Float, OCP-Tone >> Onset \
+                >> Max >> SonSeqPr >> MaxInitialV >> MaxFinalV"
+        );
+    }
+
+    #[test]
+    fn test_violation_tableau_1() {
+        // the tableau points at whatever evaluation picks, strictly or not
+        elision_data().iter().for_each(|datum| {
+            [EvalMode::Cumulative, EvalMode::Strict]
+                .iter()
+                .for_each(|mode| {
+                    let tableau = ViolationTableau::new(
+                        &datum.underlying,
+                        yoruba_ranking(&datum.underlying),
+                        &Gen::default(),
+                        *mode == EvalMode::Strict,
+                    );
+
+                    assert_eq!(
+                        tableau
+                            .rows
+                            .iter()
+                            .filter(|row| row.winner)
+                            .map(|row| row.form.to_owned())
+                            .collect::<Vec<String>>(),
+                        mode.evaluate(
+                            datum.underlying.clone(),
+                            yoruba_ranking(&datum.underlying),
+                            &Gen::default()
+                        )
+                        .into_iter()
+                        .map(String::from)
+                        .dedup()
+                        .collect::<Vec<String>>()
+                    );
+                })
+        });
+
+        let tableau = ViolationTableau::new(
+            &elision_data()[5].underlying,
+            yoruba_ranking(&elision_data()[5].underlying),
+            &Gen::default(),
+            false,
+        );
+
+        assert_eq!(
+            tableau.strata[..2],
+            [
+                vec!["Ident".to_owned(), "Dep".to_owned(), "Linearity".to_owned()],
+                vec![
+                    "Onset*3".to_owned(),
+                    "SonSeqPr".to_owned(),
+                    "Max*3".to_owned()
+                ]
+            ]
+        );
+        assert!(tableau
+            .to_string()
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("☞ ragɛdɛ "));
+    }
 }
@@ -0,0 +1,132 @@
+use crate::gen::Gen;
+use crate::harmonic::{penalty, WeightedConstraint};
+use crate::SyllabifiedCandidate;
+
+// Maximum Entropy grammars: the same weighted constraints as in harmonic.rs, but instead of only
+// the best candidate winning, every candidate gets a probability proportional to e^-penalty, so
+// variable outputs (like rɔ̀gɛ̀dɛ̀ next to ragɛ̀dɛ̀) can be modelled
+
+// every candidate GEN comes up with, in the order it comes up with them, along with its
+// probability
+pub fn evaluate_maxent(
+    underlying_candidate: SyllabifiedCandidate,
+    constraints: &[WeightedConstraint],
+    gen: &Gen,
+) -> Vec<(SyllabifiedCandidate, f64)> {
+    let penalties: Vec<(SyllabifiedCandidate, f64)> = underlying_candidate
+        .permute(gen)
+        .into_iter()
+        .map(|form| {
+            let penalty = penalty(constraints, &form);
+            (form, penalty)
+        })
+        .collect();
+
+    let probabilities = probabilities(
+        &penalties
+            .iter()
+            .map(|(_, penalty)| *penalty)
+            .collect::<Vec<f64>>(),
+    );
+
+    penalties
+        .into_iter()
+        .zip(probabilities)
+        .map(|((form, _), probability)| (form, probability))
+        .collect()
+}
+
+// candidates that sound the same (differing only in, say, whether a tone was deleted or left
+// floating) pooled together, most probable first
+pub fn surface_distribution(distribution: Vec<(SyllabifiedCandidate, f64)>) -> Vec<(String, f64)> {
+    let mut surface: Vec<(String, f64)> =
+        distribution
+            .into_iter()
+            .fold(Vec::new(), |mut surface, (form, probability)| {
+                let form = String::from(form);
+
+                match surface.iter_mut().find(|(seen, _)| *seen == form) {
+                    Some((_, total)) => *total += probability,
+                    None => surface.push((form, probability)),
+                }
+
+                surface
+            });
+
+    surface.sort_by(|(_, p1), (_, p2)| p2.total_cmp(p1));
+
+    surface
+}
+
+// e^-penalty over the sum of the same for every candidate; everything is shifted down by the
+// smallest penalty first, which doesn't change the result but keeps exp from underflowing to 0
+// when the weights are large
+pub fn probabilities(penalties: &[f64]) -> Vec<f64> {
+    let min = penalties.iter().copied().fold(f64::INFINITY, f64::min);
+
+    let scores: Vec<f64> = penalties
+        .iter()
+        .map(|penalty| (min - penalty).exp())
+        .collect();
+
+    let total: f64 = scores.iter().sum();
+
+    scores.iter().map(|score| score / total).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::constraint::{UnweightedMax, UnweightedOnset};
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_probabilities_1() {
+        let distribution = probabilities(&[1.0, 1.0, 2.0]);
+
+        assert!(close(distribution[0], distribution[1]));
+        assert!(close(distribution[0] / distribution[2], 1f64.exp()));
+        assert!(close(distribution.iter().sum(), 1.0));
+
+        // these would all underflow without the shift
+        assert!(close(probabilities(&[1000.0, 1000.0])[0], 0.5));
+    }
+
+    #[test]
+    fn test_evaluate_maxent_1() {
+        let cand: SyllabifiedCandidate = "owo-ki-owo".parse().unwrap();
+        let distribution = evaluate_maxent(
+            cand.clone(),
+            &[
+                WeightedConstraint {
+                    weight: 2.0,
+                    constraint: Box::new(UnweightedOnset),
+                },
+                WeightedConstraint {
+                    weight: 1.0,
+                    constraint: Box::new(UnweightedMax(cand)),
+                },
+            ],
+            &Gen::default(),
+        );
+
+        let probability = |surface: &str| {
+            distribution
+                .iter()
+                .find(|(form, _)| String::from(form.to_owned()) == surface)
+                .unwrap()
+                .1
+        };
+
+        assert!(close(distribution.iter().map(|(_, p)| p).sum(), 1.0));
+        // two onsetless syllables against two deletions that fix them
+        assert!(close(
+            probability("wokiwo") / probability("owokiowo"),
+            2f64.exp()
+        ));
+        assert!(close(probability("wokiwo"), probability("wokowo")));
+    }
+}
@@ -0,0 +1,403 @@
+use crate::constraint::{Constraint, ConstraintKind, RankedConstraint};
+use crate::corpus::Datum;
+use crate::erc::{ercs, Erc};
+use crate::gen::Gen;
+use crate::learn::{
+    constraint_kinds, constraint_names, tableaux, ConstraintSet, LearningError, Tableau,
+};
+use itertools::Itertools;
+use std::cmp::Reverse;
+
+// Recursive Constraint Demotion (Tesar & Smolensky): learns a ranking from observed winners by
+// comparing each of them against every loser GEN comes up with (see erc.rs). constraints that
+// never prefer a loser go into the top stratum; the ERCs those constraints take care of are thrown
+// out, and the rest of the constraints get ranked over the ERCs that are left, and so on
+
+// a stratified hierarchy, as indices into a constraint set, highest stratum first
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hierarchy {
+    pub strata: Vec<Vec<usize>>,
+}
+
+impl Hierarchy {
+    // from the ranks a hand-written ranking gives its constraints (see RankedConstraint), lowest
+    // first, so that the constraints with the same rank share a stratum
+    pub fn from_ranks(ranks: &[usize]) -> Hierarchy {
+        Hierarchy {
+            strata: ranks
+                .iter()
+                .copied()
+                .sorted()
+                .dedup()
+                .map(|rank| {
+                    (0..ranks.len())
+                        .filter(|constraint| ranks[*constraint] == rank)
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    // from Stochastic OT ranking values (see gla.rs), highest first; only constraints at exactly
+    // the same point share a stratum
+    pub fn from_ranking_values(ranking_values: &[f64]) -> Hierarchy {
+        let mut order: Vec<usize> = (0..ranking_values.len()).collect();
+
+        order.sort_by(|a, b| ranking_values[*b].total_cmp(&ranking_values[*a]));
+
+        Hierarchy {
+            strata: order
+                .into_iter()
+                .group_by(|constraint| ranking_values[*constraint].to_bits())
+                .into_iter()
+                .map(|(_, stratum)| stratum.collect())
+                .collect(),
+        }
+    }
+
+    // every constraint over every constraint in the stratum right below it; the rest of the
+    // dominations follow by transitivity
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        self.strata
+            .iter()
+            .tuple_windows()
+            .flat_map(|(higher, lower)| {
+                higher
+                    .iter()
+                    .flat_map(|higher| lower.iter().map(move |lower| (*higher, *lower)))
+            })
+            .collect()
+    }
+
+    // the constraints from a constraint set, ranked by stratum
+    pub fn ranked(&self, constraints: Vec<Box<dyn Constraint>>) -> Vec<RankedConstraint> {
+        constraints
+            .into_iter()
+            .enumerate()
+            .map(|(index, constraint)| RankedConstraint {
+                rank: self
+                    .strata
+                    .iter()
+                    .position(|stratum| stratum.contains(&index))
+                    .expect("The hierarchy is missing a constraint!")
+                    + 1,
+                constraint,
+            })
+            .collect()
+    }
+
+    // e.g. "Ident, Dep >> Onset >> Max"
+    pub fn display(&self, names: &[String]) -> String {
+        self.strata
+            .iter()
+            .map(|stratum| {
+                stratum
+                    .iter()
+                    .map(|index| names[*index].to_owned())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            })
+            .collect::<Vec<String>>()
+            .join(" >> ")
+    }
+}
+
+// every ERC for every winner against its losers, without repeats
+pub fn all_ercs(tableaux: &[Tableau]) -> Vec<Erc> {
+    tableaux
+        .iter()
+        .flat_map(ercs)
+        .fold(Vec::new(), |mut all, erc| {
+            if !all.contains(&erc) {
+                all.push(erc);
+            }
+
+            all
+        })
+}
+
+// RCD proper, over constraints numbered 0 up to names.len()
+pub fn rank(ercs: &[Erc], names: &[String]) -> Result<Hierarchy, LearningError> {
+    let mut unranked: Vec<usize> = (0..names.len()).collect();
+    let mut ercs: Vec<&Erc> = ercs.iter().collect();
+    let mut strata: Vec<Vec<usize>> = Vec::new();
+
+    while !unranked.is_empty() {
+        let (stratum, rest): (Vec<usize>, Vec<usize>) = unranked
+            .iter()
+            .partition(|constraint| !ercs.iter().any(|erc| erc.prefers_loser(**constraint)));
+
+        if stratum.is_empty() {
+            return Err(LearningError::Inconsistent {
+                unranked: unranked
+                    .iter()
+                    .map(|index| names[*index].to_owned())
+                    .collect(),
+            });
+        }
+
+        ercs.retain(|erc| {
+            !stratum
+                .iter()
+                .any(|constraint| erc.prefers_winner(*constraint))
+        });
+
+        strata.push(stratum);
+        unranked = rest;
+    }
+
+    Ok(Hierarchy { strata })
+}
+
+// Biased Constraint Demotion (Prince & Tesar): RCD, but ranking markedness over faithfulness
+// whenever the data allow it, which gets us the most restrictive grammar consistent with them.
+// markedness constraints go in as soon as they're free to; when none are, we put in as few
+// faithfulness constraints as we can get away with, picking whichever smallest set of them frees
+// up the most markedness constraints below it
+pub fn rank_biased(
+    ercs: &[Erc],
+    names: &[String],
+    kinds: &[ConstraintKind],
+) -> Result<Hierarchy, LearningError> {
+    let mut unranked: Vec<usize> = (0..names.len()).collect();
+    // losers that tie with their winners on everything can't be told apart from them by any
+    // ranking, so they'd never get thrown out
+    let mut ercs: Vec<&Erc> = ercs.iter().filter(|erc| !erc.is_trivial()).collect();
+    let mut strata: Vec<Vec<usize>> = Vec::new();
+
+    let inconsistent = |unranked: &[usize]| LearningError::Inconsistent {
+        unranked: unranked
+            .iter()
+            .map(|index| names[*index].to_owned())
+            .collect(),
+    };
+
+    while !unranked.is_empty() {
+        let available: Vec<usize> = unranked
+            .iter()
+            .copied()
+            .filter(|constraint| !ercs.iter().any(|erc| erc.prefers_loser(*constraint)))
+            .collect();
+
+        let (markedness, faithfulness): (Vec<usize>, Vec<usize>) = available
+            .iter()
+            .partition(|constraint| kinds[**constraint] == ConstraintKind::Markedness);
+
+        let stratum = if !markedness.is_empty() {
+            markedness
+        } else if ercs.is_empty() {
+            faithfulness
+        } else {
+            // only faithfulness constraints that actually prefer some winner are any use
+            let active: Vec<usize> = faithfulness
+                .iter()
+                .copied()
+                .filter(|constraint| ercs.iter().any(|erc| erc.prefers_winner(*constraint)))
+                .collect();
+
+            if active.is_empty() {
+                return Err(inconsistent(&unranked));
+            }
+
+            // how many markedness constraints would be free to go in once these are ranked
+            let freed = |subset: &[usize]| -> usize {
+                let left: Vec<&&Erc> = ercs
+                    .iter()
+                    .filter(|erc| {
+                        !subset
+                            .iter()
+                            .any(|constraint| erc.prefers_winner(*constraint))
+                    })
+                    .collect();
+
+                unranked
+                    .iter()
+                    .filter(|constraint| kinds[**constraint] == ConstraintKind::Markedness)
+                    .filter(|constraint| !left.iter().any(|erc| erc.prefers_loser(**constraint)))
+                    .count()
+            };
+
+            (1..=active.len())
+                .find_map(|size| {
+                    active
+                        .iter()
+                        .copied()
+                        .combinations(size)
+                        .map(|subset| (freed(&subset), subset))
+                        .filter(|(freed, _)| *freed > 0)
+                        // the first of the best, so that ties go to the order the constraints
+                        // were given in
+                        .min_by_key(|(freed, _)| Reverse(*freed))
+                        .map(|(_, subset)| subset)
+                })
+                .unwrap_or(active)
+        };
+
+        ercs.retain(|erc| {
+            !stratum
+                .iter()
+                .any(|constraint| erc.prefers_winner(*constraint))
+        });
+
+        unranked.retain(|constraint| !stratum.contains(constraint));
+        strata.push(stratum);
+    }
+
+    Ok(Hierarchy { strata })
+}
+
+pub fn bcd(
+    data: &[Datum],
+    constraints: ConstraintSet,
+    gen: &Gen,
+) -> Result<Hierarchy, LearningError> {
+    rank_biased(
+        &all_ercs(&tableaux(data, constraints, gen)?),
+        &constraint_names(constraints),
+        &constraint_kinds(constraints),
+    )
+}
+
+pub fn rcd(
+    data: &[Datum],
+    constraints: ConstraintSet,
+    gen: &Gen,
+) -> Result<Hierarchy, LearningError> {
+    rank(
+        &all_ercs(&tableaux(data, constraints, gen)?),
+        &constraint_names(constraints),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::erc::erc;
+
+    fn names(count: usize) -> Vec<String> {
+        (0..count).map(|index| format!("C{}", index)).collect()
+    }
+
+    #[test]
+    fn test_rank_1() {
+        // C2 >> C0 >> C1, with C3 never mattering
+        let ercs = vec![erc("WLee"), erc("LeWe")];
+
+        assert_eq!(
+            rank(&ercs, &names(4)),
+            Ok(Hierarchy {
+                strata: vec![vec![2, 3], vec![0], vec![1]]
+            })
+        );
+    }
+
+    #[test]
+    fn test_rank_2() {
+        let ercs = vec![erc("WL"), erc("LW")];
+
+        assert_eq!(
+            rank(&ercs, &names(2)),
+            Err(LearningError::Inconsistent {
+                unranked: vec!["C0".to_owned(), "C1".to_owned()]
+            })
+        );
+    }
+
+    #[test]
+    fn test_rank_biased_1() {
+        // RCD puts the faithfulness constraint C0 up top, since it never prefers a loser; BCD
+        // holds it back below the markedness constraints
+        let ercs = vec![erc("eWL")];
+        let kinds = [
+            ConstraintKind::Faithfulness,
+            ConstraintKind::Markedness,
+            ConstraintKind::Markedness,
+        ];
+
+        assert_eq!(
+            rank(&ercs, &names(3)),
+            Ok(Hierarchy {
+                strata: vec![vec![0, 1], vec![2]]
+            })
+        );
+        assert_eq!(
+            rank_biased(&ercs, &names(3), &kinds),
+            Ok(Hierarchy {
+                strata: vec![vec![1], vec![2], vec![0]]
+            })
+        );
+    }
+
+    #[test]
+    fn test_rank_biased_2() {
+        // both faithfulness constraints are free to go in, but C0 on its own frees up both
+        // markedness constraints, where C1 would only free up C3
+        let ercs = vec![erc("WeLe"), erc("WWeL")];
+        let kinds = [
+            ConstraintKind::Faithfulness,
+            ConstraintKind::Faithfulness,
+            ConstraintKind::Markedness,
+            ConstraintKind::Markedness,
+        ];
+
+        assert_eq!(
+            rank(&ercs, &names(4)),
+            Ok(Hierarchy {
+                strata: vec![vec![0, 1], vec![2, 3]]
+            })
+        );
+        assert_eq!(
+            rank_biased(&ercs, &names(4), &kinds),
+            Ok(Hierarchy {
+                strata: vec![vec![0], vec![2, 3], vec![1]]
+            })
+        );
+    }
+
+    #[test]
+    fn test_rank_biased_3() {
+        let ercs = vec![erc("WL"), erc("LW")];
+        let kinds = [ConstraintKind::Faithfulness, ConstraintKind::Markedness];
+
+        assert_eq!(
+            rank_biased(&ercs, &names(2), &kinds),
+            Err(LearningError::Inconsistent {
+                unranked: vec!["C0".to_owned(), "C1".to_owned()]
+            })
+        );
+    }
+
+    #[test]
+    fn test_from_ranks_1() {
+        assert_eq!(
+            Hierarchy::from_ranks(&[2, 1, 2, 4]),
+            Hierarchy {
+                strata: vec![vec![1], vec![0, 2], vec![3]]
+            }
+        );
+        assert_eq!(
+            Hierarchy::from_ranking_values(&[90.0, 100.0, 90.0, 95.5]),
+            Hierarchy {
+                strata: vec![vec![1], vec![3], vec![0, 2]]
+            }
+        );
+    }
+
+    #[test]
+    fn test_edges_1() {
+        let hierarchy = Hierarchy {
+            strata: vec![vec![2, 3], vec![0], vec![1]],
+        };
+
+        assert_eq!(hierarchy.edges(), vec![(2, 0), (3, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn test_display_1() {
+        let hierarchy = Hierarchy {
+            strata: vec![vec![2, 3], vec![0], vec![1]],
+        };
+
+        assert_eq!(hierarchy.display(&names(4)), "C2, C3 >> C0 >> C1");
+    }
+}
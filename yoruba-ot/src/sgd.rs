@@ -0,0 +1,321 @@
+use crate::corpus::Datum;
+use crate::gen::Gen;
+use crate::learn::{tableaux, ConstraintSet, LearningError, Tableau};
+use crate::maxent::probabilities;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+// learns MaxEnt weights (see maxent.rs) by stochastic gradient descent on the log-likelihood of
+// the data. for each observation, every weight moves by the difference between how often the
+// observed form violates its constraint and how often the grammar expects an output to; with a
+// Gaussian prior on the weights, they also get pulled back towards 0 a little every time, which
+// keeps constraints the data say little about from drifting. weights never go below 0, so they
+// can go straight into harmonic.rs as well
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SgdSettings {
+    // what every weight starts out as
+    pub initial_weight: f64,
+    pub learning_rate: f64,
+    // how many passes to make over the data
+    pub epochs: usize,
+    // the standard deviation of the Gaussian prior on the weights, if there is one
+    pub prior: Option<f64>,
+}
+
+impl Default for SgdSettings {
+    fn default() -> Self {
+        SgdSettings {
+            initial_weight: 0.0,
+            learning_rate: 0.1,
+            epochs: 100,
+            prior: None,
+        }
+    }
+}
+
+// an underlying form's candidates, with how many times each of them was observed; tableaux with
+// the same underlying form (one for each of its variable outputs) get pooled into one of these
+#[derive(Debug, Clone, PartialEq)]
+struct Form {
+    underlying: String,
+    candidates: Vec<(String, Vec<usize>)>,
+    observed: Vec<(String, usize)>,
+}
+
+impl Form {
+    fn pool(tableaux: &[Tableau]) -> Vec<Form> {
+        tableaux
+            .iter()
+            .fold(Vec::new(), |mut forms: Vec<Form>, tableau| {
+                let observed = (tableau.winner.to_owned(), tableau.frequency);
+
+                match forms
+                    .iter_mut()
+                    .find(|form| form.underlying == tableau.underlying)
+                {
+                    Some(form) => form.observed.push(observed),
+                    None => forms.push(Form {
+                        underlying: tableau.underlying.to_owned(),
+                        candidates: tableau.candidates.to_owned(),
+                        observed: vec![observed],
+                    }),
+                }
+
+                forms
+            })
+    }
+
+    fn probabilities(&self, weights: &[f64]) -> Vec<f64> {
+        probabilities(
+            &self
+                .candidates
+                .iter()
+                .map(|(_, violations)| penalty(weights, violations))
+                .collect::<Vec<f64>>(),
+        )
+    }
+
+    // how probable a surface form is, over every candidate that comes out as it
+    fn predicted(&self, probabilities: &[f64], surface: &str) -> f64 {
+        self.candidates
+            .iter()
+            .zip(probabilities)
+            .filter(|((form, _), _)| form == surface)
+            .map(|(_, probability)| probability)
+            .sum()
+    }
+
+    // how many times each constraint is violated on average, over the candidates that come out
+    // as a surface form if there is one, or over all of them if there isn't
+    fn expected_violations(&self, probabilities: &[f64], surface: Option<&str>) -> Vec<f64> {
+        let included: Vec<(&Vec<usize>, f64)> = self
+            .candidates
+            .iter()
+            .zip(probabilities)
+            .filter(|((form, _), _)| surface.is_none_or(|surface| form == surface))
+            .map(|((_, violations), probability)| (violations, *probability))
+            .collect();
+
+        let total: f64 = included.iter().map(|(_, probability)| probability).sum();
+
+        (0..self.candidates[0].1.len())
+            .map(|constraint| {
+                included
+                    .iter()
+                    .map(|(violations, probability)| violations[constraint] as f64 * probability)
+                    .sum::<f64>()
+                    / total
+            })
+            .collect()
+    }
+}
+
+fn penalty(weights: &[f64], violations: &[usize]) -> f64 {
+    weights
+        .iter()
+        .zip(violations)
+        .map(|(weight, violations)| weight * *violations as f64)
+        .sum()
+}
+
+// how well a grammar does on one observed surface form
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fit {
+    pub underlying: String,
+    pub surface: String,
+    // the share of its underlying form's observations that came out as it
+    pub observed: f64,
+    pub predicted: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SgdResult {
+    // one per constraint, in the order of the constraint set
+    pub weights: Vec<f64>,
+    // of all of the data, under the weights learned
+    pub log_likelihood: f64,
+    pub fit: Vec<Fit>,
+}
+
+// SGD over tableaux that have already been built; every observation of every datum is learned from
+// once an epoch, in a different order each time
+pub fn learn(tableaux: &[Tableau], settings: &SgdSettings, rng: &mut impl Rng) -> SgdResult {
+    let forms = Form::pool(tableaux);
+
+    let constraint_count = tableaux
+        .first()
+        .map_or(0, |tableau| tableau.winner_violations.len());
+
+    let mut weights = vec![settings.initial_weight; constraint_count];
+
+    let mut order: Vec<(&Form, &str)> = forms
+        .iter()
+        .flat_map(|form| {
+            form.observed.iter().flat_map(move |(surface, frequency)| {
+                std::iter::repeat_n((form, surface.as_str()), *frequency)
+            })
+        })
+        .collect();
+
+    // the prior is over the whole of the data, so each observation gets its share of it
+    let observations = order.len().max(1) as f64;
+
+    (0..settings.epochs).for_each(|_| {
+        order.shuffle(rng);
+
+        order.iter().for_each(|(form, surface)| {
+            let probabilities = form.probabilities(&weights);
+            let observed = form.expected_violations(&probabilities, Some(surface));
+            let expected = form.expected_violations(&probabilities, None);
+
+            weights
+                .iter_mut()
+                .enumerate()
+                .for_each(|(constraint, weight)| {
+                    let prior = settings
+                        .prior
+                        .map_or(0.0, |sigma| *weight / (sigma * sigma * observations));
+
+                    *weight = (*weight
+                        + settings.learning_rate
+                            * (expected[constraint] - observed[constraint] - prior))
+                        .max(0.0);
+                })
+        })
+    });
+
+    SgdResult {
+        log_likelihood: log_likelihood(&forms, &weights),
+        fit: fit(&forms, &weights),
+        weights,
+    }
+}
+
+fn log_likelihood(forms: &[Form], weights: &[f64]) -> f64 {
+    forms
+        .iter()
+        .map(|form| {
+            let probabilities = form.probabilities(weights);
+
+            // forms never observed don't count, even if the grammar says they can't come out
+            form.observed
+                .iter()
+                .filter(|(_, frequency)| *frequency > 0)
+                .map(|(surface, frequency)| {
+                    *frequency as f64 * form.predicted(&probabilities, surface).ln()
+                })
+                .sum::<f64>()
+        })
+        .sum()
+}
+
+fn fit(forms: &[Form], weights: &[f64]) -> Vec<Fit> {
+    forms
+        .iter()
+        .flat_map(|form| {
+            let probabilities = form.probabilities(weights);
+            let total: usize = form.observed.iter().map(|(_, frequency)| frequency).sum();
+
+            form.observed
+                .iter()
+                .map(|(surface, frequency)| Fit {
+                    underlying: form.underlying.to_owned(),
+                    surface: surface.to_owned(),
+                    observed: *frequency as f64 / total.max(1) as f64,
+                    predicted: form.predicted(&probabilities, surface),
+                })
+                .collect::<Vec<Fit>>()
+        })
+        .collect()
+}
+
+pub fn sgd(
+    data: &[Datum],
+    constraints: ConstraintSet,
+    gen: &Gen,
+    settings: &SgdSettings,
+    rng: &mut impl Rng,
+) -> Result<SgdResult, LearningError> {
+    Ok(learn(&tableaux(data, constraints, gen)?, settings, rng))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::learn::test_tableau;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn close(a: f64, b: f64, tolerance: f64) -> bool {
+        (a - b).abs() < tolerance
+    }
+
+    // one underlying form with two candidates, a violating C0 and b violating C1, with a observed
+    // some number of times and b some other number of times
+    fn variable(a: usize, b: usize) -> Vec<Tableau> {
+        let candidates = [("a", vec![1, 0]), ("b", vec![0, 1])];
+
+        vec![
+            Tableau {
+                frequency: a,
+                ..test_tableau("u", "a", &candidates)
+            },
+            Tableau {
+                frequency: b,
+                ..test_tableau("u", "b", &candidates)
+            },
+        ]
+    }
+
+    #[test]
+    fn test_learn_1() {
+        // a quarter of the time a, three quarters of the time b; a MaxEnt grammar can match that
+        // exactly, with C0 weighted ln 3 over C1
+        let result = learn(
+            &variable(1, 3),
+            &SgdSettings {
+                learning_rate: 0.01,
+                epochs: 2000,
+                ..SgdSettings::default()
+            },
+            &mut StdRng::seed_from_u64(0),
+        );
+
+        assert!(close(result.weights[0] - result.weights[1], 3f64.ln(), 0.2));
+        assert!(close(result.fit[0].observed, 0.25, 1e-9));
+        assert!(close(result.fit[0].predicted, 0.25, 0.05));
+        assert!(close(result.fit[1].predicted, 0.75, 0.05));
+        // the best it could do
+        assert!(close(
+            result.log_likelihood,
+            0.25f64.ln() + 3.0 * 0.75f64.ln(),
+            0.05
+        ));
+    }
+
+    #[test]
+    fn test_learn_2() {
+        // with only a ever observed, the weights would grow without end if nothing held them back
+        let settings = SgdSettings {
+            epochs: 1000,
+            ..SgdSettings::default()
+        };
+
+        let unbounded = learn(&variable(4, 0), &settings, &mut StdRng::seed_from_u64(0));
+        let bounded = learn(
+            &variable(4, 0),
+            &SgdSettings {
+                prior: Some(1.0),
+                ..settings
+            },
+            &mut StdRng::seed_from_u64(0),
+        );
+
+        // C0 never gets any weight, since it only ever holds back the observed form
+        assert_eq!(unbounded.weights[0], 0.0);
+        assert!(bounded.weights[1] < unbounded.weights[1]);
+        assert!(bounded.weights[1] > 0.0);
+        assert!(unbounded.fit[0].predicted > bounded.fit[0].predicted);
+    }
+}
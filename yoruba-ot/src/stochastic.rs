@@ -0,0 +1,169 @@
+use crate::constraint::Constraint;
+use crate::gen::Gen;
+use crate::SyllabifiedCandidate;
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
+
+// Stochastic OT: every constraint sits at some point on a continuous ranking scale, and each time
+// we evaluate, every one of those points gets some Gaussian noise added to it before the
+// constraints are ranked by where they ended up; constraints close together on the scale swap
+// places every so often, which gives us free variation
+
+#[derive(Debug)]
+pub struct StochasticConstraint {
+    pub ranking_value: f64,
+    pub constraint: Box<dyn Constraint>,
+}
+
+// a ranking drawn from the grammar, as indices into the constraints the ranking values belong to,
+// highest ranked first
+pub fn sample_ranking(ranking_values: &[f64], noise: f64, rng: &mut impl Rng) -> Vec<usize> {
+    let normal = Normal::new(0.0, noise).expect("Noise has to be a finite, positive number!");
+
+    let selection_points: Vec<f64> = ranking_values
+        .iter()
+        .map(|ranking_value| ranking_value + normal.sample(rng))
+        .collect();
+
+    let mut ranking: Vec<usize> = (0..ranking_values.len()).collect();
+
+    ranking.sort_by(|a, b| selection_points[*b].total_cmp(&selection_points[*a]));
+
+    ranking
+}
+
+// the indices of the candidates that win under a ranking, given each candidate's violations of
+// each constraint
+pub fn winners(profiles: &[Vec<usize>], ranking: &[usize]) -> Vec<usize> {
+    let ranked = |profile: &Vec<usize>| -> Vec<usize> {
+        ranking
+            .iter()
+            .map(|constraint| profile[*constraint])
+            .collect()
+    };
+
+    let best = profiles
+        .iter()
+        .map(ranked)
+        .min()
+        .expect("No forms to evaluate!");
+
+    (0..profiles.len())
+        .filter(|candidate| ranked(&profiles[*candidate]) == best)
+        .collect()
+}
+
+// how often each output wins over a number of evaluations, most frequent first; when several
+// candidates tie in an evaluation they split it between them
+pub fn evaluate_stochastic(
+    underlying_candidate: SyllabifiedCandidate,
+    constraints: &[StochasticConstraint],
+    gen: &Gen,
+    noise: f64,
+    trials: usize,
+    rng: &mut impl Rng,
+) -> Vec<(String, f64)> {
+    let surface_forms: Vec<SyllabifiedCandidate> = underlying_candidate.permute(gen);
+
+    // violations don't change from one evaluation to the next, so we only count them once
+    let profiles: Vec<Vec<usize>> = surface_forms
+        .iter()
+        .map(|form| {
+            constraints
+                .iter()
+                .map(|constraint| constraint.constraint.evaluate(form.to_owned()))
+                .collect()
+        })
+        .collect();
+
+    let ranking_values: Vec<f64> = constraints
+        .iter()
+        .map(|constraint| constraint.ranking_value)
+        .collect();
+
+    let mut frequencies: Vec<(String, f64)> = Vec::new();
+
+    (0..trials).for_each(|_| {
+        let winners = winners(&profiles, &sample_ranking(&ranking_values, noise, rng));
+        let share = 1.0 / (winners.len() * trials) as f64;
+
+        winners.iter().for_each(|winner| {
+            let form = String::from(surface_forms[*winner].to_owned());
+
+            match frequencies.iter_mut().find(|(seen, _)| *seen == form) {
+                Some((_, frequency)) => *frequency += share,
+                None => frequencies.push((form, share)),
+            }
+        })
+    });
+
+    frequencies.sort_by(|(_, f1), (_, f2)| f2.total_cmp(f1));
+
+    frequencies
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::constraint::{Max, Onset};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_winners_1() {
+        let profiles = vec![vec![1, 0], vec![0, 1], vec![0, 1]];
+
+        assert_eq!(winners(&profiles, &[0, 1]), vec![1, 2]);
+        assert_eq!(winners(&profiles, &[1, 0]), vec![0]);
+    }
+
+    #[test]
+    fn test_sample_ranking_1() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // far enough apart that the noise never swaps them
+        (0..100).for_each(|_| assert_eq!(sample_ranking(&[0.0, 100.0], 2.0, &mut rng), vec![1, 0]));
+    }
+
+    fn onset_against_max(max_ranking_value: f64) -> Vec<(String, f64)> {
+        let cand: SyllabifiedCandidate = "ra-o".parse().unwrap();
+
+        evaluate_stochastic(
+            cand.clone(),
+            &[
+                StochasticConstraint {
+                    ranking_value: 100.0,
+                    constraint: Box::new(Onset),
+                },
+                StochasticConstraint {
+                    ranking_value: max_ranking_value,
+                    constraint: Box::new(Max(cand)),
+                },
+            ],
+            &Gen::default(),
+            2.0,
+            1000,
+            &mut StdRng::seed_from_u64(0),
+        )
+    }
+
+    #[test]
+    fn test_evaluate_stochastic_1() {
+        let frequencies = onset_against_max(80.0);
+
+        assert_eq!(frequencies.len(), 1);
+        assert!((frequencies[0].1 - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_evaluate_stochastic_2() {
+        // with the two at the same point, each outranks the other about half of the time
+        let frequencies = onset_against_max(100.0);
+
+        assert_eq!(frequencies.len(), 2);
+        assert!(frequencies
+            .iter()
+            .all(|(_, frequency)| (frequency - 0.5).abs() < 0.1));
+        assert!((frequencies.iter().map(|(_, f)| f).sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+}
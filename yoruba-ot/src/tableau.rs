@@ -0,0 +1,512 @@
+use crate::constraint::{Constraint, RankedConstraint};
+use crate::gen::Gen;
+use crate::{strata, SyllabifiedCandidate};
+use std::cmp::Reverse;
+use unicode_segmentation::UnicodeSegmentation;
+
+// violation tableaux, drawn the way they are on paper: a row for every candidate, a column for
+// every constraint (with the constraints in a stratum side by side, split up by dotted lines), a
+// * for every violation, a ! after the violation that rules a candidate out, every cell that comes
+// after that shaded, since nothing there matters any more, and a ☞ pointing at the winners, e.g.
+//
+//     /ra-o/ | Onset | Max
+//     ☞ ra   |       | *
+//       r    |       | **!
+//       rao  | *!    | ░░░░
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cell {
+    pub violations: usize,
+    // the violation that rules the candidate out, if it's in this cell, counting from 1
+    pub fatal: Option<usize>,
+    pub shaded: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    pub form: String,
+    pub winner: bool,
+    // by stratum, then by constraint within the stratum
+    pub cells: Vec<Vec<Cell>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViolationTableau {
+    pub underlying: String,
+    // the names of the constraints, by stratum, highest first
+    pub strata: Vec<Vec<String>>,
+    // the winners first, then the losers, the ones that held out longest first
+    pub rows: Vec<Row>,
+}
+
+// where a candidate gets ruled out: the point the decision gets made at, and the cell and
+// violation that do it
+#[derive(Debug, Clone, Copy)]
+struct RuledOut {
+    point: usize,
+    cell: (usize, usize),
+    fatal: usize,
+}
+
+impl ViolationTableau {
+    // the constraints in a stratum have their violations summed up, unless evaluation is strict
+    // (see EvalMode), in which case each of them decides on its own, in the order given
+    pub fn new(
+        underlying: &SyllabifiedCandidate,
+        mut constraints: Vec<RankedConstraint>,
+        gen: &Gen,
+        strict: bool,
+    ) -> ViolationTableau {
+        let strata = strata(&mut constraints);
+
+        // GEN can come up with the same form more than once (with a tone deleted or left floating,
+        // say), and those only get one row between them: whichever does best on the ranking, going
+        // stratum by stratum, or constraint by constraint if evaluation is strict
+        let candidates: Vec<(String, Vec<Vec<usize>>)> =
+            underlying
+                .permute(gen)
+                .into_iter()
+                .fold(Vec::new(), |mut candidates, form| {
+                    let violations: Vec<Vec<usize>> = strata
+                        .iter()
+                        .map(|stratum| {
+                            stratum
+                                .iter()
+                                .map(|constraint| constraint.evaluate(form.to_owned()))
+                                .collect()
+                        })
+                        .collect();
+                    let form = String::from(form);
+                    let totals = |violations: &[Vec<usize>]| -> Vec<usize> {
+                        if strict {
+                            violations.concat()
+                        } else {
+                            violations
+                                .iter()
+                                .map(|stratum| stratum.iter().sum())
+                                .collect()
+                        }
+                    };
+
+                    match candidates.iter_mut().find(|(other, _)| *other == form) {
+                        Some((_, other)) if totals(&violations) < totals(other) => {
+                            *other = violations
+                        }
+                        Some(_) => (),
+                        None => candidates.push((form, violations)),
+                    }
+
+                    candidates
+                });
+
+        // where the decisions get made: each stratum as a whole, or each constraint on its own
+        let points: Vec<Vec<(usize, usize)>> = strata
+            .iter()
+            .enumerate()
+            .flat_map(|(index, stratum)| {
+                let cells: Vec<(usize, usize)> = (0..stratum.len())
+                    .map(|constraint| (index, constraint))
+                    .collect();
+
+                if strict {
+                    cells.into_iter().map(|cell| vec![cell]).collect()
+                } else {
+                    vec![cells]
+                }
+            })
+            .collect();
+
+        let point_of = |cell: (usize, usize)| -> usize {
+            points
+                .iter()
+                .position(|point| point.contains(&cell))
+                .expect("Every cell is decided somewhere!")
+        };
+
+        let mut ruled_out: Vec<Option<RuledOut>> = vec![None; candidates.len()];
+
+        points.iter().enumerate().for_each(|(index, point)| {
+            let total = |violations: &[Vec<usize>]| -> usize {
+                point
+                    .iter()
+                    .map(|(stratum, constraint)| violations[*stratum][*constraint])
+                    .sum()
+            };
+
+            let best = candidates
+                .iter()
+                .zip(&ruled_out)
+                .filter(|(_, ruled_out)| ruled_out.is_none())
+                .map(|((_, violations), _)| total(violations))
+                .min()
+                .unwrap_or(0);
+
+            candidates
+                .iter()
+                .zip(ruled_out.iter_mut())
+                .filter(|((_, violations), ruled_out)| {
+                    ruled_out.is_none() && total(violations) > best
+                })
+                .for_each(|((_, violations), ruled_out)| {
+                    // the first violation past what the best candidates get away with
+                    let mut before = 0;
+
+                    let (cell, fatal) = point
+                        .iter()
+                        .find_map(|(stratum, constraint)| {
+                            let count = violations[*stratum][*constraint];
+
+                            if before + count > best {
+                                Some(((*stratum, *constraint), best + 1 - before))
+                            } else {
+                                before += count;
+                                None
+                            }
+                        })
+                        .expect("A candidate was ruled out without going over!");
+
+                    *ruled_out = Some(RuledOut {
+                        point: index,
+                        cell,
+                        fatal,
+                    });
+                });
+        });
+
+        // the winners only get shaded past the point where the last of the losers went
+        let decided = ruled_out
+            .iter()
+            .flatten()
+            .map(|ruled_out| ruled_out.point)
+            .max();
+
+        let mut rows: Vec<(Option<usize>, Row)> = candidates
+            .into_iter()
+            .zip(ruled_out)
+            .map(|((form, violations), ruled_out)| {
+                let last = ruled_out.map_or(decided, |ruled_out| Some(ruled_out.point));
+
+                let cells = violations
+                    .iter()
+                    .enumerate()
+                    .map(|(stratum, violations)| {
+                        violations
+                            .iter()
+                            .enumerate()
+                            .map(|(constraint, violations)| Cell {
+                                violations: *violations,
+                                fatal: ruled_out
+                                    .filter(|ruled_out| ruled_out.cell == (stratum, constraint))
+                                    .map(|ruled_out| ruled_out.fatal),
+                                shaded: last
+                                    .is_some_and(|last| point_of((stratum, constraint)) > last),
+                            })
+                            .collect()
+                    })
+                    .collect();
+
+                (
+                    ruled_out.map(|ruled_out| ruled_out.point),
+                    Row {
+                        // so that deleting everything still shows up as something
+                        form: if form.is_empty() {
+                            "∅".to_owned()
+                        } else {
+                            form
+                        },
+                        winner: ruled_out.is_none(),
+                        cells,
+                    },
+                )
+            })
+            .collect();
+
+        // a stable sort, so candidates ruled out at the same point keep the order GEN made them in
+        rows.sort_by_key(|(point, _)| Reverse(point.unwrap_or(usize::MAX)));
+
+        ViolationTableau {
+            underlying: underlying.morphemes(),
+            strata: strata
+                .iter()
+                .map(|stratum| stratum.iter().map(|constraint| constraint.name()).collect())
+                .collect(),
+            rows: rows.into_iter().map(|(_, row)| row).collect(),
+        }
+    }
+}
+
+impl Cell {
+    // e.g. **!* for three violations, the second of them fatal
+    pub fn marks(&self) -> String {
+        match self.fatal {
+            Some(fatal) => format!(
+                "{}!{}",
+                "*".repeat(fatal),
+                "*".repeat(self.violations - fatal)
+            ),
+            None => "*".repeat(self.violations),
+        }
+    }
+}
+
+impl std::fmt::Display for ViolationTableau {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // in graphemes, so that tone marks and tie bars don't throw off the columns
+        let width = |str: &str| str.graphemes(true).count();
+        let pad =
+            |str: &str, to: usize, with: &str| format!("{}{}", str, with.repeat(to - width(str)));
+
+        let heading = format!("/{}/", self.underlying);
+        let forms: Vec<String> = self
+            .rows
+            .iter()
+            .map(|row| format!("{} {}", if row.winner { "☞" } else { " " }, row.form))
+            .collect();
+
+        let first = forms
+            .iter()
+            .chain(std::iter::once(&heading))
+            .map(|form| width(form))
+            .max()
+            .unwrap_or(0);
+
+        let widths: Vec<Vec<usize>> = self
+            .strata
+            .iter()
+            .enumerate()
+            .map(|(stratum, names)| {
+                names
+                    .iter()
+                    .enumerate()
+                    .map(|(constraint, name)| {
+                        self.rows
+                            .iter()
+                            .map(|row| width(&row.cells[stratum][constraint].marks()))
+                            .chain(std::iter::once(width(name)))
+                            .max()
+                            .unwrap_or(0)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // strata are split up by solid lines and the constraints within them by dotted ones
+        let line = |first_column: &str, columns: Vec<Vec<String>>| -> String {
+            std::iter::once(pad(first_column, first, " "))
+                .chain(columns.iter().map(|stratum| stratum.join(" : ")))
+                .collect::<Vec<String>>()
+                .join(" | ")
+                .trim_end()
+                .to_owned()
+        };
+
+        writeln!(
+            f,
+            "{}",
+            line(
+                &heading,
+                self.strata
+                    .iter()
+                    .zip(&widths)
+                    .map(|(names, widths)| {
+                        names
+                            .iter()
+                            .zip(widths)
+                            .map(|(name, width)| pad(name, *width, " "))
+                            .collect()
+                    })
+                    .collect()
+            )
+        )?;
+
+        self.rows.iter().zip(&forms).try_for_each(|(row, form)| {
+            writeln!(
+                f,
+                "{}",
+                line(
+                    form,
+                    row.cells
+                        .iter()
+                        .zip(&widths)
+                        .map(|(cells, widths)| {
+                            cells
+                                .iter()
+                                .zip(widths)
+                                .map(|(cell, width)| {
+                                    pad(&cell.marks(), *width, if cell.shaded { "░" } else { " " })
+                                })
+                                .collect()
+                        })
+                        .collect()
+                )
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::constraint::{MaxTone, NoFloat, UnweightedMax, UnweightedOnset};
+
+    fn onset_and_max(cand: &SyllabifiedCandidate, max_rank: usize) -> Vec<RankedConstraint> {
+        vec![
+            RankedConstraint {
+                rank: 1,
+                constraint: Box::new(UnweightedOnset),
+            },
+            RankedConstraint {
+                rank: max_rank,
+                constraint: Box::new(UnweightedMax(cand.clone())),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_new_1() {
+        let cand: SyllabifiedCandidate = "ra-o".parse().unwrap();
+        let tableau = ViolationTableau::new(&cand, onset_and_max(&cand, 2), &Gen::default(), false);
+
+        assert_eq!(
+            tableau.strata,
+            vec![vec!["Onset".to_owned()], vec!["Max".to_owned()]]
+        );
+        assert_eq!(
+            tableau
+                .rows
+                .iter()
+                .filter(|row| row.winner)
+                .map(|row| row.form.to_owned())
+                .collect::<Vec<String>>(),
+            vec!["ra", "ro"]
+        );
+        // the onsetless ones go first, so everything past Onset is shaded for them
+        assert_eq!(
+            tableau.rows.last().unwrap().cells,
+            vec![
+                vec![Cell {
+                    violations: 1,
+                    fatal: Some(1),
+                    shaded: false
+                }],
+                vec![Cell {
+                    violations: 2,
+                    fatal: None,
+                    shaded: true
+                }]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_new_2() {
+        let cand: SyllabifiedCandidate = "ra-o".parse().unwrap();
+
+        // summed up in one stratum, rao does as well as ra and ro, and the ! for ao is the second
+        // Onset violation; the Max violation after it is in the same stratum, so it isn't shaded
+        let cumulative =
+            ViolationTableau::new(&cand, onset_and_max(&cand, 1), &Gen::default(), false);
+        let ao = cumulative.rows.iter().find(|row| row.form == "ao").unwrap();
+
+        assert_eq!(cumulative.rows.iter().filter(|row| row.winner).count(), 3);
+        assert_eq!(ao.cells[0][0].fatal, Some(2));
+        assert!(!ao.cells[0][1].shaded);
+
+        // but strictly, Onset rules rao out on its own, and ao doesn't make it to Max
+        let strict = ViolationTableau::new(&cand, onset_and_max(&cand, 1), &Gen::default(), true);
+        let ao = strict.rows.iter().find(|row| row.form == "ao").unwrap();
+
+        assert_eq!(strict.rows.iter().filter(|row| row.winner).count(), 2);
+        assert_eq!(ao.cells[0][0].fatal, Some(1));
+        assert!(ao.cells[0][1].shaded);
+    }
+
+    #[test]
+    fn test_new_3() {
+        let cand: SyllabifiedCandidate = "ra-ɔ̀gɛ̀dɛ̀".parse().unwrap();
+        let mut constraints = onset_and_max(&cand, 2);
+        constraints.push(RankedConstraint {
+            rank: 3,
+            constraint: Box::new(NoFloat),
+        });
+        constraints.push(RankedConstraint {
+            rank: 4,
+            constraint: Box::new(MaxTone(cand.clone())),
+        });
+        let gen = Gen {
+            floating_tones: true,
+            ..Gen::default()
+        };
+        let tableau = ViolationTableau::new(&cand, constraints, &gen, false);
+
+        let elided: Vec<&Row> = tableau
+            .rows
+            .iter()
+            .filter(|row| row.form == "ragɛ̀dɛ̀")
+            .collect();
+
+        // the ɔ's tone could go with it or be left floating, but ragɛ̀dɛ̀ only gets the one row,
+        // with the tone gone since *Float outranks MaxTone
+        assert_eq!(elided.len(), 1);
+        assert_eq!(elided[0].cells[2][0].violations, 0);
+        assert_eq!(elided[0].cells[3][0].violations, 1);
+    }
+
+    #[test]
+    fn test_new_4() {
+        let cand: SyllabifiedCandidate = "ra-ɔ̀gɛ̀dɛ̀".parse().unwrap();
+        let gen = Gen {
+            floating_tones: true,
+            ..Gen::default()
+        };
+        let elided = |first: Box<dyn Constraint>, second: Box<dyn Constraint>| -> Vec<usize> {
+            let mut constraints = onset_and_max(&cand, 2);
+            constraints.push(RankedConstraint {
+                rank: 3,
+                constraint: first,
+            });
+            constraints.push(RankedConstraint {
+                rank: 3,
+                constraint: second,
+            });
+            let tableau = ViolationTableau::new(&cand, constraints, &gen, true);
+            let row = tableau
+                .rows
+                .iter()
+                .find(|row| row.form == "ragɛ̀dɛ̀")
+                .unwrap();
+
+            row.cells[2].iter().map(|cell| cell.violations).collect()
+        };
+
+        // in one stratum, deleting the tone and leaving it floating each violate it once, but
+        // strictly, whichever comes first in the stratum decides between them
+        assert_eq!(
+            elided(Box::new(NoFloat), Box::new(MaxTone(cand.clone()))),
+            vec![0, 1]
+        );
+        assert_eq!(
+            elided(Box::new(MaxTone(cand.clone())), Box::new(NoFloat)),
+            vec![0, 1]
+        );
+    }
+
+    #[test]
+    fn test_display_1() {
+        let cand: SyllabifiedCandidate = "ra-o".parse().unwrap();
+        let tableau = ViolationTableau::new(&cand, onset_and_max(&cand, 2), &Gen::default(), false);
+
+        assert_eq!(
+            tableau.to_string(),
+            "/ra-o/ | Onset | Max\n\
+             ☞ ra   |       | *\n\
+             ☞ ro   |       | *\n\
+             \u{20} r    |       | **!\n\
+             \u{20} ∅    |       | **!*\n\
+             \u{20} rao  | *!    | ░░░░\n\
+             \u{20} ao   | *!*   | *░░░\n\
+             \u{20} a    | *!    | **░░\n\
+             \u{20} o    | *!    | **░░\n"
+        );
+    }
+}
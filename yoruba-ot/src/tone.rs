@@ -0,0 +1,178 @@
+use crate::{Segment, SegmentType};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ToneLevel {
+    High,
+    Mid,
+    Low,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tone {
+    pub level: ToneLevel,
+    // correspondent of the vowel this tone is associated to; none if the tone is floating
+    pub anchor: Option<usize>,
+    // position of the underlying tone this one corresponds to
+    pub correspondent: Option<usize>,
+}
+
+// combining diacritics, as typed after a bare vowel
+const TONE_MARKS: [(char, ToneLevel); 3] = [
+    ('\u{301}', ToneLevel::High),
+    ('\u{304}', ToneLevel::Mid),
+    ('\u{300}', ToneLevel::Low),
+];
+
+// precomposed vowels, which is how most Yoruba text is typed; ɛ and ɔ have no precomposed forms
+const TONED_VOWELS: [(char, char, ToneLevel); 15] = [
+    ('á', 'a', ToneLevel::High),
+    ('é', 'e', ToneLevel::High),
+    ('í', 'i', ToneLevel::High),
+    ('ó', 'o', ToneLevel::High),
+    ('ú', 'u', ToneLevel::High),
+    ('ā', 'a', ToneLevel::Mid),
+    ('ē', 'e', ToneLevel::Mid),
+    ('ī', 'i', ToneLevel::Mid),
+    ('ō', 'o', ToneLevel::Mid),
+    ('ū', 'u', ToneLevel::Mid),
+    ('à', 'a', ToneLevel::Low),
+    ('è', 'e', ToneLevel::Low),
+    ('ì', 'i', ToneLevel::Low),
+    ('ò', 'o', ToneLevel::Low),
+    ('ù', 'u', ToneLevel::Low),
+];
+
+// splits the tone marks off of a grapheme, e.g. "ɔ̀" -> ("ɔ", [Low])
+//
+// unmarked vowels come back toneless rather than mid, since Yoruba doesn't mark mid tone
+pub fn split_tones(grapheme: &str) -> (String, Vec<ToneLevel>) {
+    grapheme.chars().fold(
+        (String::new(), Vec::new()),
+        |(mut base, mut levels), char| {
+            if let Some((_, level)) = TONE_MARKS.iter().find(|(mark, _)| *mark == char) {
+                levels.push(*level);
+            } else if let Some((_, bare, level)) =
+                TONED_VOWELS.iter().find(|(toned, _, _)| *toned == char)
+            {
+                base.push(*bare);
+                levels.push(*level);
+            } else {
+                base.push(char);
+            }
+
+            (base, levels)
+        },
+    )
+}
+
+// the inverse of split_tones
+pub fn join_tones(base: &str, levels: &[ToneLevel]) -> String {
+    let precomposed = match levels {
+        [level] => TONED_VOWELS
+            .iter()
+            .find(|(_, bare, toned_level)| base == bare.to_string() && toned_level == level)
+            .map(|(toned, _, _)| toned.to_string()),
+        _ => None,
+    };
+
+    precomposed.unwrap_or_else(|| {
+        levels.iter().fold(base.to_owned(), |mut joined, level| {
+            joined.push(
+                TONE_MARKS
+                    .iter()
+                    .find(|(_, mark_level)| mark_level == level)
+                    .unwrap()
+                    .0,
+            );
+            joined
+        })
+    })
+}
+
+// every tonal tier that can go with a surface form; tones stay with their vowels if those survive,
+// and otherwise are deleted along with them, or, when reassociation is on, left floating or docked
+// onto the closest surviving vowel on either side
+pub fn permute_tones(tones: &[Tone], form: &[Segment], reassociate: bool) -> Vec<Vec<Tone>> {
+    tones.iter().fold(vec![vec![]], |tiers, tone| {
+        let fates = fates(tone, form, reassociate);
+
+        tiers
+            .iter()
+            .flat_map(|tier| {
+                fates.iter().map(|fate| {
+                    let mut tier = tier.to_owned();
+                    tier.extend(fate.to_owned());
+                    tier
+                })
+            })
+            .collect()
+    })
+}
+
+fn fates(tone: &Tone, form: &[Segment], reassociate: bool) -> Vec<Vec<Tone>> {
+    let anchor = match tone.anchor {
+        Some(anchor) if form.iter().all(|seg| seg.correspondent != Some(anchor)) => anchor,
+        // still associated to a surviving vowel, or already floating
+        _ => return vec![vec![tone.to_owned()]],
+    };
+
+    if !reassociate {
+        return vec![vec![]];
+    }
+
+    let hosts: Vec<usize> = form
+        .iter()
+        .filter(|seg| seg.seg_type == SegmentType::Vowel)
+        .filter_map(|seg| seg.correspondent)
+        .collect();
+
+    let left = hosts.iter().filter(|host| **host < anchor).max();
+    let right = hosts.iter().filter(|host| **host > anchor).min();
+
+    // deleted, floating, or docked
+    vec![
+        vec![],
+        vec![Tone {
+            anchor: None,
+            ..tone.to_owned()
+        }],
+    ]
+    .into_iter()
+    .chain([left, right].into_iter().flatten().map(|host| {
+        vec![Tone {
+            anchor: Some(*host),
+            ..tone.to_owned()
+        }]
+    }))
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_split_tones_1() {
+        assert_eq!(split_tones("ó"), ("o".to_owned(), vec![ToneLevel::High]));
+        assert_eq!(
+            split_tones("ɔ\u{300}"),
+            ("ɔ".to_owned(), vec![ToneLevel::Low])
+        );
+        assert_eq!(split_tones("a"), ("a".to_owned(), vec![]));
+        assert_eq!(
+            split_tones("a\u{300}\u{301}"),
+            ("a".to_owned(), vec![ToneLevel::Low, ToneLevel::High])
+        );
+    }
+
+    #[test]
+    fn test_join_tones_1() {
+        assert_eq!(join_tones("o", &[ToneLevel::High]), "ó");
+        assert_eq!(join_tones("ɔ", &[ToneLevel::Low]), "ɔ\u{300}");
+        assert_eq!(join_tones("a", &[]), "a");
+        assert_eq!(
+            join_tones("a", &[ToneLevel::Low, ToneLevel::High]),
+            "a\u{300}\u{301}"
+        );
+    }
+}
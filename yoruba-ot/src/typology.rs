@@ -0,0 +1,351 @@
+use crate::erc::{consistent, Erc};
+use crate::gen::Gen;
+use crate::learn::ConstraintSet;
+use crate::stochastic::winners;
+use crate::SyllabifiedCandidate;
+use itertools::Itertools;
+
+// factorial typology: every language a constraint set predicts for some underlying forms. a
+// language here is just what each of the underlying forms maps to; different rankings can come up
+// with the same one
+//
+// there are two ways of going about it. the obvious one tries every total ranking and sees what
+// comes out, which is n! rankings for n constraints. the other builds languages up one underlying
+// form at a time: each language so far gets extended with each candidate the next form could come
+// out as, and the extension is kept if the ERCs it takes (the candidate beating all of the others,
+// along with everything the language needed already) are still consistent. languages that can't be
+// had die off as soon as they show up, so this only ever looks at as many as are actually out there
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Method {
+    #[default]
+    Ercs,
+    BruteForce,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Language {
+    // each underlying form, with whatever wins for it; there can be more than one winner when
+    // candidates violate every constraint the same number of times
+    pub outputs: Vec<(String, Vec<String>)>,
+    // what it takes for a ranking to come up with this language: every winner against every loser
+    pub ercs: Vec<Erc>,
+    // every total ranking that comes up with it, as indices into the constraint set, highest first,
+    // if we've gone through them (leaving out the constraints that don't matter)
+    pub rankings: Option<Vec<Vec<usize>>>,
+}
+
+// an underlying form's candidates, with the ones that violate every constraint the same number of
+// times grouped together, since no ranking could ever pick between them
+pub struct Candidates {
+    pub underlying: String,
+    pub groups: Vec<(Vec<String>, Vec<usize>)>,
+}
+
+impl Candidates {
+    pub fn new(
+        underlying: &SyllabifiedCandidate,
+        constraints: ConstraintSet,
+        gen: &Gen,
+    ) -> Candidates {
+        let constraints = constraints(underlying);
+
+        let groups = underlying.permute(gen).into_iter().fold(
+            Vec::new(),
+            |mut groups: Vec<(Vec<String>, Vec<usize>)>, form| {
+                let profile: Vec<usize> = constraints
+                    .iter()
+                    .map(|constraint| constraint.evaluate(form.to_owned()))
+                    .collect();
+                let form = String::from(form);
+
+                match groups.iter_mut().find(|(_, seen)| *seen == profile) {
+                    Some((forms, _)) => {
+                        if !forms.contains(&form) {
+                            forms.push(form);
+                            forms.sort();
+                        }
+                    }
+                    None => groups.push((vec![form], profile)),
+                }
+
+                groups
+            },
+        );
+
+        Candidates {
+            underlying: String::from(underlying.to_owned()),
+            groups,
+        }
+    }
+
+    fn profiles(&self) -> Vec<Vec<usize>> {
+        self.groups
+            .iter()
+            .map(|(_, profile)| profile.to_owned())
+            .collect()
+    }
+
+    // the group that wins under a ranking; every group violates something a different number of
+    // times, so there's only ever one
+    pub fn winner(&self, ranking: &[usize]) -> usize {
+        winners(&self.profiles(), ranking)[0]
+    }
+
+    // one group against every other
+    pub fn ercs(&self, winner: usize) -> Vec<Erc> {
+        let (winners, winner_profile) = &self.groups[winner];
+
+        self.groups
+            .iter()
+            .enumerate()
+            .filter(|(loser, _)| *loser != winner)
+            .map(|(_, (losers, loser_profile))| {
+                Erc::new(&winners[0], &losers[0], winner_profile, loser_profile)
+            })
+            .collect()
+    }
+}
+
+// the constraints that tell some candidates of some underlying form apart; the rest never decide
+// anything, so wherever they're ranked, the same language comes out
+pub fn relevant(candidates: &[Candidates], constraint_count: usize) -> Vec<usize> {
+    (0..constraint_count)
+        .filter(|constraint| {
+            candidates.iter().any(|candidates| {
+                candidates
+                    .groups
+                    .iter()
+                    .map(|(_, profile)| profile[*constraint])
+                    .dedup()
+                    .count()
+                    > 1
+            })
+        })
+        .collect()
+}
+
+pub fn factorial_typology(
+    underlying: &[SyllabifiedCandidate],
+    constraints: ConstraintSet,
+    gen: &Gen,
+    method: Method,
+) -> Vec<Language> {
+    let candidates: Vec<Candidates> = underlying
+        .iter()
+        .map(|underlying| Candidates::new(underlying, constraints, gen))
+        .collect();
+
+    match method {
+        Method::Ercs => by_ercs(&candidates),
+        Method::BruteForce => by_brute_force(
+            &candidates,
+            constraints(&SyllabifiedCandidate {
+                form: vec![],
+                tones: vec![],
+            })
+            .len(),
+        ),
+    }
+}
+
+fn language(
+    candidates: &[Candidates],
+    winners: &[usize],
+) -> (Vec<(String, Vec<String>)>, Vec<Erc>) {
+    candidates
+        .iter()
+        .zip(winners)
+        .map(|(candidates, winner)| {
+            (
+                (
+                    candidates.underlying.to_owned(),
+                    candidates.groups[*winner].0.to_owned(),
+                ),
+                candidates.ercs(*winner),
+            )
+        })
+        .fold(
+            (Vec::new(), Vec::new()),
+            |(mut outputs, mut ercs), (output, more)| {
+                outputs.push(output);
+                more.into_iter().for_each(|erc| {
+                    if !ercs.contains(&erc) {
+                        ercs.push(erc)
+                    }
+                });
+
+                (outputs, ercs)
+            },
+        )
+}
+
+fn by_ercs(candidates: &[Candidates]) -> Vec<Language> {
+    // each language so far as which group won for each of the underlying forms so far, along with
+    // the ERCs that takes
+    let languages: Vec<(Vec<usize>, Vec<Erc>)> =
+        candidates
+            .iter()
+            .fold(vec![(Vec::new(), Vec::new())], |languages, candidates| {
+                // groups that lose under every ranking (harmonically bounded ones) can't win in any
+                // language, so they're thrown out before we try them against every language so far
+                let contenders: Vec<(usize, Vec<Erc>)> = (0..candidates.groups.len())
+                    .map(|winner| (winner, candidates.ercs(winner)))
+                    .filter(|(_, ercs)| consistent(&ercs.iter().collect::<Vec<&Erc>>()))
+                    .collect();
+
+                languages
+                    .iter()
+                    .flat_map(|(winners, ercs)| {
+                        contenders.iter().filter_map(move |(winner, new)| {
+                            let ercs: Vec<Erc> = ercs.iter().chain(new).cloned().collect();
+
+                            consistent(&ercs.iter().collect::<Vec<&Erc>>()).then(|| {
+                                let mut winners = winners.to_owned();
+                                winners.push(*winner);
+
+                                (winners, ercs)
+                            })
+                        })
+                    })
+                    .collect()
+            });
+
+    languages
+        .iter()
+        .map(|(winners, _)| {
+            let (outputs, ercs) = language(candidates, winners);
+
+            Language {
+                outputs,
+                ercs,
+                rankings: None,
+            }
+        })
+        .collect()
+}
+
+// goes through every ranking of the constraints that matter, so this is only feasible for up to
+// nine or ten of them
+fn by_brute_force(candidates: &[Candidates], constraint_count: usize) -> Vec<Language> {
+    let relevant = relevant(candidates, constraint_count);
+
+    let languages = relevant.iter().copied().permutations(relevant.len()).fold(
+        Vec::new(),
+        |mut languages: Vec<(Vec<usize>, Vec<Vec<usize>>)>, ranking| {
+            let winners: Vec<usize> = candidates
+                .iter()
+                .map(|candidates| candidates.winner(&ranking))
+                .collect();
+
+            match languages.iter_mut().find(|(seen, _)| *seen == winners) {
+                Some((_, rankings)) => rankings.push(ranking),
+                None => languages.push((winners, vec![ranking])),
+            }
+
+            languages
+        },
+    );
+
+    languages
+        .into_iter()
+        .map(|(winners, rankings)| {
+            let (outputs, ercs) = language(candidates, &winners);
+
+            Language {
+                outputs,
+                ercs,
+                rankings: Some(rankings),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::constraint::{Constraint, NoFloat, UnweightedMax, UnweightedOnset};
+
+    fn onset_and_max(cand: &SyllabifiedCandidate) -> Vec<Box<dyn Constraint>> {
+        vec![
+            Box::new(UnweightedOnset),
+            Box::new(UnweightedMax(cand.clone())),
+            Box::new(NoFloat),
+        ]
+    }
+
+    fn outputs(languages: &[Language]) -> Vec<Vec<(String, Vec<String>)>> {
+        languages
+            .iter()
+            .map(|language| language.outputs.to_owned())
+            .sorted()
+            .collect()
+    }
+
+    #[test]
+    fn test_factorial_typology_1() {
+        let underlying: Vec<SyllabifiedCandidate> =
+            vec!["ra-o".parse().unwrap(), "ba".parse().unwrap()];
+        let languages = factorial_typology(
+            &underlying,
+            onset_and_max,
+            &Gen::default(),
+            Method::BruteForce,
+        );
+
+        // NoFloat doesn't matter without floating tones, so there are two rankings, and each of
+        // them comes up with a language of its own
+        assert_eq!(languages.len(), 2);
+        assert_eq!(
+            languages[0].outputs,
+            vec![
+                ("rao".to_owned(), vec!["ra".to_owned(), "ro".to_owned()]),
+                ("ba".to_owned(), vec!["ba".to_owned()])
+            ]
+        );
+        assert_eq!(languages[0].rankings, Some(vec![vec![0, 1]]));
+        assert_eq!(
+            languages[1].outputs,
+            vec![
+                ("rao".to_owned(), vec!["rao".to_owned()]),
+                ("ba".to_owned(), vec!["ba".to_owned()])
+            ]
+        );
+        assert_eq!(languages[1].rankings, Some(vec![vec![1, 0]]));
+
+        // and going by ERCs gets the same languages
+        assert_eq!(
+            outputs(&factorial_typology(
+                &underlying,
+                onset_and_max,
+                &Gen::default(),
+                Method::Ercs
+            )),
+            outputs(&languages)
+        );
+    }
+
+    #[test]
+    fn test_ercs_1() {
+        let candidates = Candidates::new(&"ra-o".parse().unwrap(), onset_and_max, &Gen::default());
+
+        // rao, ra and ro, r, ao, a and o, and nothing at all
+        assert_eq!(candidates.groups.len(), 6);
+        assert_eq!(
+            candidates.groups[1].0,
+            vec!["ra".to_owned(), "ro".to_owned()]
+        );
+
+        // deleting both vowels is harmonically bounded by deleting one of them, and so is deleting
+        // the consonant along with a vowel
+        assert!(!consistent(
+            &candidates.ercs(2).iter().collect::<Vec<&Erc>>()
+        ));
+        assert!(!consistent(
+            &candidates.ercs(4).iter().collect::<Vec<&Erc>>()
+        ));
+        assert!(consistent(
+            &candidates.ercs(1).iter().collect::<Vec<&Erc>>()
+        ));
+    }
+}
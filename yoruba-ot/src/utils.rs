@@ -35,6 +35,90 @@ pub fn permute_delete<A: Clone>(list: &Vec<A>) -> Vec<Vec<A>> {
     }
 }
 
+// replaces at most `max` elements of the list, each with any of the replacements offered for it;
+// the first permutation returned is always the list itself
+pub fn permute_replace<A: Clone, F: Fn(&A) -> Vec<A>>(
+    list: &[A],
+    replacements: &F,
+    max: usize,
+) -> Vec<Vec<A>> {
+    match (list, max) {
+        ([], _) => vec![vec![]],
+        ([elem, elems @ ..], 0) => {
+            append_to_all(elem.to_owned(), permute_replace(elems, replacements, 0))
+        }
+        ([elem, elems @ ..], _) => {
+            append_to_all(elem.to_owned(), permute_replace(elems, replacements, max))
+                .append_ret(
+                    &mut replacements(elem)
+                        .into_iter()
+                        .flat_map(|replacement| {
+                            append_to_all(
+                                replacement,
+                                permute_replace(elems, replacements, max - 1),
+                            )
+                        })
+                        .collect(),
+                )
+                .to_vec()
+        }
+    }
+}
+
+// swaps at most `max` pairs of adjacent elements, where no element is swapped more than once; the
+// first permutation returned is always the list itself
+pub fn permute_swap<A: Clone>(list: &[A], max: usize) -> Vec<Vec<A>> {
+    match (list, max) {
+        ([first, second, elems @ ..], 1..) => {
+            append_to_all(first.to_owned(), permute_swap(&list[1..], max))
+                .append_ret(&mut append_to_all(
+                    second.to_owned(),
+                    append_to_all(first.to_owned(), permute_swap(elems, max - 1)),
+                ))
+                .to_vec()
+        }
+        _ => vec![list.to_vec()],
+    }
+}
+
+// every sequence of at most `max` elements drawn from `inventory`, starting with the empty one
+fn sequences<A: Clone>(inventory: &[A], max: usize) -> Vec<Vec<A>> {
+    match max {
+        0 => vec![vec![]],
+        _ => vec![vec![]]
+            .append_ret(
+                &mut inventory
+                    .iter()
+                    .flat_map(|elem| append_to_all(elem.to_owned(), sequences(inventory, max - 1)))
+                    .collect(),
+            )
+            .to_vec(),
+    }
+}
+
+// inserts at most `max` elements from `inventory` in total, anywhere in the list; the first
+// permutation returned is always the list itself
+pub fn permute_insert<A: Clone>(list: &[A], inventory: &[A], max: usize) -> Vec<Vec<A>> {
+    match list {
+        [] => sequences(inventory, max),
+        [elem, elems @ ..] => sequences(inventory, max)
+            .iter()
+            .flat_map(|inserted| {
+                permute_insert(elems, inventory, max - inserted.len())
+                    .iter()
+                    .map(|rest| {
+                        inserted
+                            .to_owned()
+                            .push_ret(elem.to_owned())
+                            .append_ret(&mut rest.to_owned())
+                            .to_owned()
+                    })
+                    .collect::<Vec<Vec<A>>>()
+            })
+            .collect(),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -63,4 +147,104 @@ mod test {
         ];
         assert_eq!(expected, ret_value)
     }
+
+    #[test]
+    fn test_permute_insert_1() {
+        let vec = vec!['a', 'b'];
+        let ret_value = permute_insert(&vec, &['x'], 1);
+        let expected = vec![
+            vec!['a', 'b'],
+            vec!['a', 'b', 'x'],
+            vec!['a', 'x', 'b'],
+            vec!['x', 'a', 'b'],
+        ];
+        assert_eq!(expected, ret_value)
+    }
+
+    #[test]
+    fn test_permute_insert_2() {
+        let vec = vec!['a'];
+        let ret_value = permute_insert(&vec, &['x', 'y'], 2);
+        let expected = vec![
+            vec!['a'],
+            vec!['a', 'x'],
+            vec!['a', 'x', 'x'],
+            vec!['a', 'x', 'y'],
+            vec!['a', 'y'],
+            vec!['a', 'y', 'x'],
+            vec!['a', 'y', 'y'],
+            vec!['x', 'a'],
+            vec!['x', 'a', 'x'],
+            vec!['x', 'a', 'y'],
+            vec!['x', 'x', 'a'],
+            vec!['x', 'y', 'a'],
+            vec!['y', 'a'],
+            vec!['y', 'a', 'x'],
+            vec!['y', 'a', 'y'],
+            vec!['y', 'x', 'a'],
+            vec!['y', 'y', 'a'],
+        ];
+        assert_eq!(expected, ret_value)
+    }
+
+    #[test]
+    fn test_permute_replace_1() {
+        let vec = vec!['a', 'b', 'c'];
+        let ret_value = permute_replace(
+            &vec,
+            &|elem: &char| match elem {
+                'a' => vec!['x'],
+                'c' => vec!['y', 'z'],
+                _ => vec![],
+            },
+            1,
+        );
+        let expected = vec![
+            vec!['a', 'b', 'c'],
+            vec!['a', 'b', 'y'],
+            vec!['a', 'b', 'z'],
+            vec!['x', 'b', 'c'],
+        ];
+        assert_eq!(expected, ret_value)
+    }
+
+    #[test]
+    fn test_permute_replace_2() {
+        let vec = vec!['a', 'b'];
+        let ret_value = permute_replace(&vec, &|_: &char| vec!['x'], 2);
+        let expected = vec![
+            vec!['a', 'b'],
+            vec!['a', 'x'],
+            vec!['x', 'b'],
+            vec!['x', 'x'],
+        ];
+        assert_eq!(expected, ret_value)
+    }
+
+    #[test]
+    fn test_permute_swap_1() {
+        let vec = vec!['a', 'b', 'c', 'd'];
+        let ret_value = permute_swap(&vec, 2);
+        let expected = vec![
+            vec!['a', 'b', 'c', 'd'],
+            vec!['a', 'b', 'd', 'c'],
+            vec!['a', 'c', 'b', 'd'],
+            vec!['b', 'a', 'c', 'd'],
+            vec!['b', 'a', 'd', 'c'],
+        ];
+        assert_eq!(expected, ret_value)
+    }
+
+    #[test]
+    fn test_permute_swap_2() {
+        let vec = vec!['a', 'b', 'c', 'd'];
+        let ret_value = permute_swap(&vec, 1);
+        let expected = vec![
+            vec!['a', 'b', 'c', 'd'],
+            vec!['a', 'b', 'd', 'c'],
+            vec!['a', 'c', 'b', 'd'],
+            vec!['b', 'a', 'c', 'd'],
+        ];
+        assert_eq!(expected, ret_value)
+    }
 }